@@ -32,49 +32,393 @@
 //! let session = Session::new(1000);
 //! assert!(matches!(session.auth(), Auth::User(session)));
 //! ```
+//!
+//! ## typed roles
+//!
+//! Matching on the enum is still a runtime check, every function needing an admin
+//! has to match again. Instead each [Auth] variant carries an [Authenticated] witness,
+//! a [Certified] value tagged with a zero-sized role: [Anonymous], [User], [Moderator] or [Admin].
+//!
+//! ```
+//! # use bear_witness::auth::*;
+//! #
+//! let session = Session { user_id: 0 };
+//! if let Auth::Admin(admin) = authenticate(session) {
+//!     // no runtime check, the witness proves we are an admin
+//!     assert_eq!(get_admin_page(&admin), "<html>admin</html>");
+//! }
+//! ```
+//!
+//! A [User] witness is not accepted where an [Admin] is required.
+//! ```compile_fail
+//! # use bear_witness::auth::*;
+//! #
+//! let session = Session { user_id: 1000 };
+//! if let Auth::User(user) = authenticate(session) {
+//!     get_admin_page(&user);
+//!     // error: mismatched types, expected `Certified<Session, Admin>`
+//! }
+//! ```
+//!
+//! Roles form a hierarchy: `Admin > Moderator > User > Anonymous`.
+//! A witness can be downgraded to any role it [Subsumes], but never upgraded.
+//! ```
+//! # use bear_witness::auth::*;
+//! #
+//! # let session = Session { user_id: 0 };
+//! # let Auth::Admin(admin) = authenticate(session) else { unreachable!() };
+//! let moderator: Authenticated<Moderator> = admin.downgrade();
+//! let user: Authenticated<User> = moderator.downgrade();
+//! assert_eq!(user.user_id, 0);
+//! ```
+//!
+//! ```compile_fail
+//! # use bear_witness::auth::*;
+//! #
+//! # let session = Session { user_id: 0 };
+//! # let Auth::Admin(admin) = authenticate(session) else { unreachable!() };
+//! let moderator: Authenticated<Moderator> = admin.downgrade();
+//! let admin: Authenticated<Admin> = moderator.downgrade();
+//! // error: the trait bound `Moderator: Subsumes<Admin>` is not satisfied
+//! ```
+//!
+//! ## pluggable authenticators
+//!
+//! [authenticate] hard-codes the `user_id == 0` rule of the [DefaultAuthenticator].
+//! Any policy implementing [Authenticator] produces the same witness types,
+//! see [policy] for a static user table, a role lookup closure and a password verifier.
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::policy::*;
+//! #
+//! let users: StaticUsers = [(7, Role::Moderator)].into_iter().collect();
+//! let Auth::Moderator(moderator) = users.authenticate(Session { user_id: 7 }, &()) else {
+//!     unreachable!()
+//! };
+//! let user: Authenticated<User> = moderator.downgrade();
+//! ```
+//!
+//! ## capabilities
+//!
+//! For permissions finer than a role, see [capability].
+//!
+//! ## expiring witnesses
+//!
+//! For witnesses which expire or can be revoked, see [expiry].
+//!
+//! ## route guards
+//!
+//! Routes declare the witness they need and the [Router] resolves it,
+//! see [guard].
+//!
+//! ## audit
+//!
+//! To know when and why a witness was produced, wrap the [Authenticator]
+//! with [Audited](audit::Audited), or install a sink for [authenticate]
+//! with [set_default_sink](audit::set_default_sink), see [audit].
+//!
+//! ## errors
+//!
+//! Failures are reported as a structured [AuthError], so callers can branch on them.
+//! ```
+//! # use bear_witness::auth::*;
+//! #
+//! let auth = authenticate(Session { user_id: 1000 });
+//! let error = auth.require::<Admin>().unwrap_err();
+//! assert_eq!(
+//!     error,
+//!     AuthError::Forbidden { required_role: Role::Admin, actual_role: Role::User },
+//! );
+//! assert_eq!(error.to_string(), "forbidden: requires Admin, got User");
+//! ```
+//!
+//! Role witnesses cannot be forged outside of this module.
+//! ```compile_fail
+//! # use bear_witness::auth::*;
+//! # use bear_witness::Certified;
+//! #
+//! let admin: Authenticated<Admin> = Certified::new(Session { user_id: 1000 }, Admin(()));
+//! // error: cannot initialize a tuple struct which contains private fields
+//! ```
+
+use std::fmt;
+
+use crate::Certified;
+use capability::Permission;
+use guard::{Rejection, Request, Router};
+
+pub mod audit;
+pub mod capability;
+pub mod expiry;
+pub mod guard;
+pub mod policy;
 
 /// Route handler.
 ///
 /// 1. get current session
-/// 2. authenticate
+/// 2. authenticate, resolving the [Authenticated] witness the route needs
 /// 3. get_admin_page()
-pub fn handler() -> Result<String, String> {
-    let session = Session { user_id: 0 };
-    let auth = authenticate(session);
-    get_admin_page(&auth)
+pub fn handler() -> Result<String, Rejection> {
+    let router = Router::new(DefaultAuthenticator)
+        .route("/admin", |admin: Authenticated<Admin>| {
+            get_admin_page(&admin)
+        });
+    router.dispatch(Request {
+        path: "/admin".to_string(),
+        session: Some(Session { user_id: 0 }),
+        credentials: &(),
+    })
 }
 
 /// User session, identified by `user_id`.
+#[derive(Debug)]
 pub struct Session {
     /// User Id for the current session.
     pub user_id: u32,
 }
 
-/// Type witness for the [Session::user_id] value.
+/// Role enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    /// Anonymous
+    Anonymous,
+    /// User
+    User,
+    /// Moderator
+    Moderator,
+    /// Admin
+    Admin,
+}
+
+mod sealed {
+    /// Only this crate can construct a [Token], and therefore a typed role.
+    pub struct Token(pub(super) ());
+
+    pub trait Sealed: Sized {
+        fn grant(token: Token) -> Self;
+    }
+}
+
+/// Typed role trait, so we can require a role in function signatures.
+///
+/// Used as the proof of an [Authenticated] witness.
+pub trait TypedRole: sealed::Sealed {
+    /// The [Role] value of this type.
+    const ROLE: Role;
+}
+
+/// The typed role `Self` includes all the rights of the role `R`.
+pub trait Subsumes<R: TypedRole>: TypedRole {}
+
+macro_rules! typed_roles {
+    ($($role:ident: $($lower:ident),*;)*) => {$(
+        #[doc = concat!("Typed [Role::", stringify!($role), "]")]
+        pub struct $role(());
+        impl sealed::Sealed for $role {
+            fn grant(_token: sealed::Token) -> Self {
+                $role(())
+            }
+        }
+        impl TypedRole for $role {
+            const ROLE: Role = Role::$role;
+        }
+        $(impl Subsumes<$lower> for $role {})*
+    )*};
+}
+typed_roles! {
+    Anonymous: Anonymous;
+    User: User, Anonymous;
+    Moderator: Moderator, User, Anonymous;
+    Admin: Admin, Moderator, User, Anonymous;
+}
+
+/// Type witness of a [Session] authenticated with the role `R`.
+pub type Authenticated<R, T = Session> = Certified<T, R>;
+
+impl<T, R: TypedRole> Certified<T, R> {
+    /// Grant the typed role `R`, only this module decides when.
+    fn grant(t: T) -> Self {
+        Certified::new(t, R::grant(sealed::Token(())))
+    }
+
+    /// The [Role] value of this witness.
+    pub fn role(&self) -> Role {
+        R::ROLE
+    }
+
+    /// Downgrade to a lower role, the typed role `R` has to [Subsumes] it.
+    pub fn downgrade<Lower: TypedRole>(self) -> Authenticated<Lower, T>
+    where
+        R: Subsumes<Lower>,
+    {
+        Certified::grant(self.into_inner())
+    }
+}
+
+/// Type witness for the [Role] an [Authenticator] granted.
+#[derive(Debug)]
 pub enum Auth<T> {
-    /// [Auth::Admin] <=> ([Session::user_id] == 0)
-    Admin(T),
-    /// [Auth::User] <=> ([Session::user_id] != 0)
-    User(T),
+    /// [Auth::Admin] <=> ([Session::user_id] == 0) for [authenticate]
+    Admin(Authenticated<Admin, T>),
+    /// Moderators are never produced by [authenticate].
+    Moderator(Authenticated<Moderator, T>),
+    /// [Auth::User] <=> ([Session::user_id] != 0) for [authenticate]
+    User(Authenticated<User, T>),
+    /// Anonymous users are never produced by [authenticate].
+    Anonymous(Authenticated<Anonymous, T>),
+}
+impl<T> Auth<T> {
+    /// Lift the [Role] value into type.
+    fn lift(t: T, role: Role) -> Self {
+        match role {
+            Role::Admin => Auth::Admin(Certified::grant(t)),
+            Role::Moderator => Auth::Moderator(Certified::grant(t)),
+            Role::User => Auth::User(Certified::grant(t)),
+            Role::Anonymous => Auth::Anonymous(Certified::grant(t)),
+        }
+    }
+
+    /// The [Role] value of the witness.
+    pub fn role(&self) -> Role {
+        match self {
+            Auth::Admin(_) => Role::Admin,
+            Auth::Moderator(_) => Role::Moderator,
+            Auth::User(_) => Role::User,
+            Auth::Anonymous(_) => Role::Anonymous,
+        }
+    }
+
+    /// Require at least the typed role `R`, downgrading the witness to it.
+    ///
+    /// The [Auth] is returned back if the granted role is lower.
+    pub fn at_least<R: TypedRole>(self) -> Result<Authenticated<R, T>, Self> {
+        if self.role() < R::ROLE {
+            return Err(self);
+        }
+        let t = match self {
+            Auth::Admin(auth) => auth.into_inner(),
+            Auth::Moderator(auth) => auth.into_inner(),
+            Auth::User(auth) => auth.into_inner(),
+            Auth::Anonymous(auth) => auth.into_inner(),
+        };
+        Ok(Certified::grant(t))
+    }
+
+    /// Require at least the typed role `R`, like [Auth::at_least] but failing with an [AuthError].
+    pub fn require<R: TypedRole>(self) -> Result<Authenticated<R, T>, AuthError> {
+        self.at_least::<R>().map_err(|auth| match auth.role() {
+            Role::Anonymous => AuthError::Unauthenticated,
+            actual_role => AuthError::Forbidden {
+                required_role: R::ROLE,
+                actual_role,
+            },
+        })
+    }
+}
+
+/// Why an auth API did not produce a witness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// There is no authenticated session, or it is [Role::Anonymous].
+    Unauthenticated,
+    /// The session is authenticated, but with a lower role.
+    Forbidden {
+        /// The role the API needs.
+        required_role: Role,
+        /// The role granted to the session.
+        actual_role: Role,
+    },
+    /// The time to live of the witness has elapsed.
+    Expired,
+    /// The user has been revoked since the witness was issued.
+    Revoked,
+    /// The policy does not grant the permission.
+    PermissionDenied {
+        /// The permission the API needs.
+        permission: Permission,
+    },
+    /// Two witnesses of different sessions were combined.
+    SessionMismatch {
+        /// The user of the first witness.
+        user_id: u32,
+        /// The user of the other witness.
+        other_user_id: u32,
+    },
+}
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::Unauthenticated => write!(f, "unauthenticated"),
+            AuthError::Forbidden {
+                required_role,
+                actual_role,
+            } => write!(
+                f,
+                "forbidden: requires {:?}, got {:?}",
+                required_role, actual_role
+            ),
+            AuthError::Expired => write!(f, "expired"),
+            AuthError::Revoked => write!(f, "revoked"),
+            AuthError::PermissionDenied { permission } => {
+                write!(f, "permission denied: requires {:?}", permission)
+            }
+            AuthError::SessionMismatch {
+                user_id,
+                other_user_id,
+            } => write!(
+                f,
+                "session mismatch: user {} combined with user {}",
+                user_id, other_user_id
+            ),
+        }
+    }
+}
+impl std::error::Error for AuthError {}
+
+/// Authentication policy, producing [Auth] witnesses.
+///
+/// Implementors only decide the [Role], the witness is always constructed by this module.
+/// See [policy] for some ready-made authenticators.
+pub trait Authenticator {
+    /// Credentials presented along with the [Session], `()` if the policy does not need any.
+    type Credentials: ?Sized;
+
+    /// Decide the [Role] of the session, [Role::Anonymous] if it cannot be authenticated.
+    fn role(&self, session: &Session, credentials: &Self::Credentials) -> Role;
+
+    /// Authenticate a user [Session], the returned [Auth] is
+    /// a type witness for the [Role] decided by [Authenticator::role].
+    fn authenticate(&self, session: Session, credentials: &Self::Credentials) -> Auth<Session> {
+        let role = self.role(&session, credentials);
+        Auth::lift(session, role)
+    }
+}
+
+/// The default [Authenticator], [Role::Admin] <=> ([Session::user_id] == 0).
+pub struct DefaultAuthenticator;
+impl Authenticator for DefaultAuthenticator {
+    type Credentials = ();
+
+    fn role(&self, session: &Session, _credentials: &()) -> Role {
+        if session.user_id == 0 {
+            Role::Admin
+        } else {
+            Role::User
+        }
+    }
 }
 
-/// Authenticate a user [Session], the returned [Auth] is
+/// Authenticate a user [Session] with the [DefaultAuthenticator], the returned [Auth] is
 /// a type witness for the [Session::user_id].
 ///
 /// [Auth::Admin] <=> ([Session::user_id] == 0)
+///
+/// The granted role is recorded to the [DefaultSink](audit::DefaultSink).
 pub fn authenticate(session: Session) -> Auth<Session> {
-    if session.user_id == 0 {
-        Auth::Admin(session)
-    } else {
-        Auth::User(session)
-    }
+    audit::Audited::new(DefaultAuthenticator, audit::DefaultSink).authenticate(session, &())
 }
 
-/// Return the admin page for [Auth::Admin], or 404.
-pub fn get_admin_page(auth: &Auth<Session>) -> Result<String, String> {
-    if let Auth::Admin(_) = auth {
-        Ok("<html>admin</html>".to_string())
-    } else {
-        Err("404".to_string())
-    }
+/// Return the admin page, only an [Admin] witness is accepted.
+pub fn get_admin_page(_admin: &Authenticated<Admin>) -> String {
+    "<html>admin</html>".to_string()
 }
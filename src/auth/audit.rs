@@ -0,0 +1,208 @@
+//! Audit log of witness issuance.
+//!
+//! Wrap any [Authenticator] with [Audited] to notify an [AuditSink]
+//! every time a [Role] is granted, i.e. every time a witness is produced.
+//! [Role::Anonymous] is not a granted role, it is not recorded.
+//!
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::audit::*;
+//! #
+//! let sink = MemorySink::default();
+//! let authenticator = Audited::new(DefaultAuthenticator, &sink);
+//! let auth = authenticator.authenticate(Session { user_id: 0 }, &());
+//! assert!(matches!(auth, Auth::Admin(_)));
+//!
+//! let events = sink.events();
+//! assert_eq!(events.len(), 1);
+//! assert_eq!((events[0].user_id, events[0].role), (0, Role::Admin));
+//! ```
+//!
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::audit::*;
+//! # use bear_witness::auth::policy::*;
+//! #
+//! let sink = MemorySink::default();
+//! let users: StaticUsers = [(7, Role::Moderator)].into_iter().collect();
+//! let authenticator = Audited::new(users, &sink);
+//! assert!(matches!(authenticator.authenticate(Session { user_id: 8 }, &()), Auth::Anonymous(_)));
+//! assert_eq!(sink.events(), vec![]);
+//! ```
+//!
+//! The default [authenticate](super::authenticate) path notifies the sink installed with
+//! [set_default_sink], if any.
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::audit::*;
+//! #
+//! let sink: &'static MemorySink = Box::leak(Box::default());
+//! set_default_sink(sink);
+//! authenticate(Session { user_id: 1000 });
+//!
+//! let events = sink.events();
+//! assert_eq!((events[0].user_id, events[0].role), (1000, Role::User));
+//! ```
+//!
+//! For local deployments, [JsonLinesSink] appends one JSON object per line to a file.
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::audit::*;
+//! #
+//! let path = std::env::temp_dir().join(format!("bear_witness_audit_{}.jsonl", std::process::id()));
+//! # let _ = std::fs::remove_file(&path);
+//! let sink = JsonLinesSink::open(&path).unwrap();
+//! let authenticator = Audited::new(DefaultAuthenticator, sink);
+//! authenticator.authenticate(Session { user_id: 0 }, &());
+//! authenticator.authenticate(Session { user_id: 1000 }, &());
+//!
+//! let log = std::fs::read_to_string(&path).unwrap();
+//! let lines: Vec<&str> = log.lines().collect();
+//! assert_eq!(lines.len(), 2);
+//! assert!(lines[0].starts_with(r#"{"user_id":0,"role":"Admin","timestamp":"#));
+//! assert!(lines[1].starts_with(r#"{"user_id":1000,"role":"User","timestamp":"#));
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+//!
+//! If the event cannot be recorded, the authentication fails closed with [Role::Anonymous].
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{Authenticator, Role, Session};
+
+/// A [Role] has been granted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    /// The authenticated [Session::user_id].
+    pub user_id: u32,
+    /// The granted [Role].
+    pub role: Role,
+    /// When was the role granted.
+    pub timestamp: SystemTime,
+}
+impl AuditEvent {
+    /// Serialize as a single line JSON object, the timestamp in seconds since the unix epoch.
+    pub fn to_json(&self) -> String {
+        let timestamp = self
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        format!(
+            r#"{{"user_id":{},"role":"{:?}","timestamp":{}}}"#,
+            self.user_id, self.role, timestamp
+        )
+    }
+}
+
+/// Destination of [AuditEvent]s.
+pub trait AuditSink {
+    /// Record the event.
+    fn record(&self, event: &AuditEvent) -> io::Result<()>;
+}
+impl<S: AuditSink + ?Sized> AuditSink for &S {
+    fn record(&self, event: &AuditEvent) -> io::Result<()> {
+        (**self).record(event)
+    }
+}
+
+/// Keep the [AuditEvent]s in memory, for tests.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    events: Mutex<Vec<AuditEvent>>,
+}
+impl MemorySink {
+    /// All the events recorded so far.
+    pub fn events(&self) -> Vec<AuditEvent> {
+        let events = self.events.lock().unwrap_or_else(PoisonError::into_inner);
+        events.clone()
+    }
+}
+impl AuditSink for MemorySink {
+    fn record(&self, event: &AuditEvent) -> io::Result<()> {
+        let mut events = self.events.lock().unwrap_or_else(PoisonError::into_inner);
+        events.push(event.clone());
+        Ok(())
+    }
+}
+
+/// Append the [AuditEvent]s to a file, one JSON object per line.
+#[derive(Debug)]
+pub struct JsonLinesSink {
+    file: Mutex<File>,
+}
+impl JsonLinesSink {
+    /// Open the file for appending, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+impl AuditSink for JsonLinesSink {
+    fn record(&self, event: &AuditEvent) -> io::Result<()> {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        writeln!(file, "{}", event.to_json())
+    }
+}
+
+static DEFAULT_SINK: RwLock<Option<Box<dyn AuditSink + Send + Sync>>> = RwLock::new(None);
+
+/// Install the [AuditSink] notified by [authenticate](super::authenticate),
+/// replacing the previous one.
+pub fn set_default_sink(sink: impl AuditSink + Send + Sync + 'static) {
+    let mut default = DEFAULT_SINK.write().unwrap_or_else(PoisonError::into_inner);
+    *default = Some(Box::new(sink));
+}
+
+/// Forward to the sink installed with [set_default_sink], nothing is recorded if there is none.
+#[derive(Debug)]
+pub struct DefaultSink;
+impl AuditSink for DefaultSink {
+    fn record(&self, event: &AuditEvent) -> io::Result<()> {
+        let default = DEFAULT_SINK.read().unwrap_or_else(PoisonError::into_inner);
+        match default.as_ref() {
+            Some(sink) => sink.record(event),
+            None => Ok(()),
+        }
+    }
+}
+
+/// [Authenticator] notifying an [AuditSink] of every granted [Role].
+pub struct Audited<A, S> {
+    authenticator: A,
+    sink: S,
+}
+impl<A, S> Audited<A, S> {
+    /// Wrap the `authenticator`, recording to the `sink`.
+    pub fn new(authenticator: A, sink: S) -> Self {
+        Self {
+            authenticator,
+            sink,
+        }
+    }
+}
+impl<A: Authenticator, S: AuditSink> Authenticator for Audited<A, S> {
+    type Credentials = A::Credentials;
+
+    fn role(&self, session: &Session, credentials: &A::Credentials) -> Role {
+        let role = self.authenticator.role(session, credentials);
+        if role == Role::Anonymous {
+            return role;
+        }
+        let event = AuditEvent {
+            user_id: session.user_id,
+            role,
+            timestamp: SystemTime::now(),
+        };
+        match self.sink.record(&event) {
+            Ok(()) => role,
+            Err(_) => Role::Anonymous,
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Capability witnesses, a type-level set of granted permissions.
+//!
+//! Roles are coarse, handlers often need finer permissions.
+//! A [Capability] is a [Certified] [Session] tagged with a [Permissions] set,
+//! each permission being either [Granted] or [Denied] at the type level.
+//!
+//! Handlers declare the permissions they need with the [Has] trait.
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::capability::*;
+//! #
+//! fn write_post<P: Has<ReadPosts> + Has<WritePosts>>(capability: &Capability<P>) -> String {
+//!     format!("post written by {}", capability.user_id)
+//! }
+//!
+//! let Auth::User(user) = authenticate(Session { user_id: 1000 }) else { unreachable!() };
+//! let capability = user.capabilities(); // read:posts, write:posts
+//! assert_eq!(write_post(&capability), "post written by 1000");
+//! ```
+//!
+//! The compiler rejects calls missing a permission.
+//! ```compile_fail
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::capability::*;
+//! #
+//! fn delete_user<P: Has<DeleteUsers>>(capability: &Capability<P>) {}
+//!
+//! let Auth::User(user) = authenticate(Session { user_id: 1000 }) else { unreachable!() };
+//! delete_user(&user.capabilities());
+//! // error: the trait bound `Permissions<Granted, Granted, Denied, Denied>: Has<DeleteUsers>` is not satisfied
+//! ```
+//!
+//! Combining two witnesses of the same [Session] yields the union of their permissions.
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::capability::*;
+//! #
+//! # fn write_post<P: Has<ReadPosts> + Has<WritePosts>>(capability: &Capability<P>) {}
+//! fn delete_user<P: Has<DeleteUsers>>(capability: &Capability<P>) {}
+//!
+//! // a one-off grant, decided by the policy of the service
+//! let grants: StaticGrants = [(1000, Permission::DeleteUsers)].into_iter().collect();
+//!
+//! let Auth::User(user) = authenticate(Session { user_id: 1000 }) else { unreachable!() };
+//! let capability = user.capabilities();
+//! let Auth::User(user) = authenticate(Session { user_id: 1000 }) else { unreachable!() };
+//! let delete = grant::<DeleteUsers, _>(user, &grants).unwrap();
+//!
+//! let capability = capability.union(delete).unwrap();
+//! write_post(&capability);
+//! delete_user(&capability);
+//!
+//! // failures are reported as an AuthError
+//! let Auth::User(other) = authenticate(Session { user_id: 7 }) else { unreachable!() };
+//! assert_eq!(
+//!     grant::<DeleteUsers, _>(other, &grants).unwrap_err(),
+//!     AuthError::PermissionDenied { permission: Permission::DeleteUsers },
+//! );
+//! let Auth::User(other) = authenticate(Session { user_id: 7 }) else { unreachable!() };
+//! assert_eq!(
+//!     capability.union(other.capabilities()).unwrap_err(),
+//!     AuthError::SessionMismatch { user_id: 1000, other_user_id: 7 },
+//! );
+//! ```
+//!
+//! Grants consume an [Authenticated] witness, a bare [Session] is not enough.
+//! ```compile_fail
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::capability::*;
+//! #
+//! let grants: StaticGrants = [(42, Permission::DeleteUsers)].into_iter().collect();
+//! let delete = grant::<DeleteUsers, _>(Session { user_id: 42 }, &grants);
+//! // error: mismatched types, expected `Certified<Session, _>`, found `Session`
+//! ```
+//!
+//! An [Expiring](super::expiry::Expiring) witness only lends its witness out,
+//! so it cannot be turned into a capability outliving its expiry or revocation.
+//! ```compile_fail
+//! # use std::time::Duration;
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::capability::*;
+//! # use bear_witness::auth::expiry::*;
+//! #
+//! let authenticator = Revocable::new(DefaultAuthenticator);
+//! let admin = authenticator
+//!     .issue::<Admin>(Session { user_id: 0 }, &(), Duration::from_millis(1))
+//!     .unwrap();
+//! let grants: StaticGrants = [(0, Permission::DeleteUsers)].into_iter().collect();
+//! let delete = grant::<DeleteUsers, _>(*admin.witness().unwrap(), &grants);
+//! // error: cannot move out of a shared reference
+//! ```
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::expiry::*;
+//! #
+//! let authenticator = Revocable::new(DefaultAuthenticator);
+//! let admin = authenticator
+//!     .issue::<Admin>(Session { user_id: 0 }, &(), Duration::from_secs(60))
+//!     .unwrap();
+//! authenticator.revoke(0);
+//! // a revoked (or expired) witness is not even lent out
+//! assert!(matches!(admin.witness(), Err(AuthError::Revoked)));
+//! ```
+//!
+//! Permission sets cannot be forged outside of this module.
+//! ```compile_fail
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::capability::*;
+//! # use bear_witness::Certified;
+//! # use std::marker::PhantomData;
+//! #
+//! let capability: Capability<<Admin as RolePermissions>::Permissions> =
+//!     Certified::new(Session { user_id: 1000 }, Permissions(PhantomData));
+//! // error: cannot initialize a tuple struct which contains private fields
+//! ```
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use super::{
+    Admin, Anonymous, AuthError, Authenticated, Moderator, Role, Session, TypedRole, User,
+};
+use crate::Certified;
+
+/// Type witness of a [Session] holding the permission set `P`.
+pub type Capability<P> = Certified<Session, P>;
+
+/// Permission enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// read:posts
+    ReadPosts,
+    /// write:posts
+    WritePosts,
+    /// delete:posts
+    DeletePosts,
+    /// delete:users
+    DeleteUsers,
+}
+
+/// Type-level flag of a single permission in [Permissions].
+pub trait Flag {
+    /// Type-level `or`.
+    type Or<B: Flag>: Flag;
+    /// Value of the flag.
+    const GRANTED: bool;
+}
+/// The permission is granted.
+pub struct Granted;
+impl Flag for Granted {
+    type Or<B: Flag> = Granted;
+    const GRANTED: bool = true;
+}
+/// The permission is denied.
+pub struct Denied;
+impl Flag for Denied {
+    type Or<B: Flag> = B;
+    const GRANTED: bool = false;
+}
+
+/// Type-level set of permissions, a [Flag] for each [Permission].
+///
+/// The private field ensures only this module can construct it.
+pub struct Permissions<ReadPosts, WritePosts, DeletePosts, DeleteUsers>(
+    PhantomData<(ReadPosts, WritePosts, DeletePosts, DeleteUsers)>,
+);
+impl<A: Flag, B: Flag, C: Flag, D: Flag> Permissions<A, B, C, D> {
+    /// The granted [Permission] values.
+    pub fn granted() -> Vec<Permission> {
+        [
+            (A::GRANTED, Permission::ReadPosts),
+            (B::GRANTED, Permission::WritePosts),
+            (C::GRANTED, Permission::DeletePosts),
+            (D::GRANTED, Permission::DeleteUsers),
+        ]
+        .into_iter()
+        .filter_map(|(granted, permission)| granted.then_some(permission))
+        .collect()
+    }
+}
+
+/// The permission set includes the permission `P`.
+pub trait Has<P: TypedPermission> {}
+
+mod sealed {
+    /// Only this module can construct a [Token], and therefore a [Permissions](super::Permissions) set.
+    pub struct Token(pub(super) ());
+
+    pub trait Sealed {}
+
+    pub trait Mint {
+        fn mint(token: Token) -> Self;
+    }
+}
+impl<A, B, C, D> sealed::Mint for Permissions<A, B, C, D> {
+    fn mint(_token: sealed::Token) -> Self {
+        Permissions(PhantomData)
+    }
+}
+
+/// Typed permission trait, so we can require a permission in function signatures.
+pub trait TypedPermission: sealed::Sealed {
+    /// The [Permission] value of this type.
+    const PERMISSION: Permission;
+    /// The [Permissions] set granting only this permission.
+    type Only: sealed::Mint;
+}
+
+macro_rules! typed_permissions {
+    ($($permission:ident: $only:ty => impl<$($flag:ident),*> $has:ty;)*) => {$(
+        #[doc = concat!("Typed [Permission::", stringify!($permission), "]")]
+        pub struct $permission;
+        impl sealed::Sealed for $permission {}
+        impl TypedPermission for $permission {
+            const PERMISSION: Permission = Permission::$permission;
+            type Only = $only;
+        }
+        impl<$($flag),*> Has<$permission> for $has {}
+    )*};
+}
+typed_permissions! {
+    ReadPosts: Permissions<Granted, Denied, Denied, Denied> => impl<B, C, D> Permissions<Granted, B, C, D>;
+    WritePosts: Permissions<Denied, Granted, Denied, Denied> => impl<A, C, D> Permissions<A, Granted, C, D>;
+    DeletePosts: Permissions<Denied, Denied, Granted, Denied> => impl<A, B, D> Permissions<A, B, Granted, D>;
+    DeleteUsers: Permissions<Denied, Denied, Denied, Granted> => impl<A, B, C> Permissions<A, B, C, Granted>;
+}
+
+/// The [Permissions] granted to a typed role.
+pub trait RolePermissions: TypedRole {
+    /// Granted permissions.
+    type Permissions: sealed::Mint;
+}
+impl RolePermissions for Anonymous {
+    type Permissions = Permissions<Granted, Denied, Denied, Denied>;
+}
+impl RolePermissions for User {
+    type Permissions = Permissions<Granted, Granted, Denied, Denied>;
+}
+impl RolePermissions for Moderator {
+    type Permissions = Permissions<Granted, Granted, Granted, Denied>;
+}
+impl RolePermissions for Admin {
+    type Permissions = Permissions<Granted, Granted, Granted, Granted>;
+}
+
+impl<R: RolePermissions> Authenticated<R> {
+    /// Turn the role witness into the [Capability] of all its [RolePermissions].
+    pub fn capabilities(self) -> Capability<R::Permissions> {
+        Certified::new(self.into_inner(), sealed::Mint::mint(sealed::Token(())))
+    }
+}
+
+/// Policy granting one-off [Permission]s beyond the [RolePermissions] of a witness.
+///
+/// Like an [Authenticator](super::Authenticator), implementors only decide,
+/// the [Capability] is always constructed by [grant].
+pub trait GrantPolicy {
+    /// Decide whether the authenticated session with the `role` gets the `permission`.
+    fn allows(&self, session: &Session, role: Role, permission: Permission) -> bool;
+}
+
+/// Static table of [Session::user_id] -> granted [Permission]s.
+pub struct StaticGrants {
+    grants: HashMap<u32, Vec<Permission>>,
+}
+impl FromIterator<(u32, Permission)> for StaticGrants {
+    fn from_iter<I: IntoIterator<Item = (u32, Permission)>>(iter: I) -> Self {
+        let mut grants: HashMap<u32, Vec<Permission>> = HashMap::new();
+        for (user_id, permission) in iter {
+            grants.entry(user_id).or_default().push(permission);
+        }
+        Self { grants }
+    }
+}
+impl GrantPolicy for StaticGrants {
+    fn allows(&self, session: &Session, _role: Role, permission: Permission) -> bool {
+        self.grants
+            .get(&session.user_id)
+            .is_some_and(|permissions| permissions.contains(&permission))
+    }
+}
+
+/// Grant the single permission `P` to the [Authenticated] session, if the `policy` allows it.
+///
+/// The witness is consumed, like [Authenticated::capabilities].
+pub fn grant<P: TypedPermission, R: TypedRole>(
+    witness: Authenticated<R>,
+    policy: &(impl GrantPolicy + ?Sized),
+) -> Result<Capability<P::Only>, AuthError> {
+    if policy.allows(&witness, R::ROLE, P::PERMISSION) {
+        Ok(Certified::new(
+            witness.into_inner(),
+            sealed::Mint::mint(sealed::Token(())),
+        ))
+    } else {
+        Err(AuthError::PermissionDenied {
+            permission: P::PERMISSION,
+        })
+    }
+}
+
+impl<A: Flag, B: Flag, C: Flag, D: Flag> Capability<Permissions<A, B, C, D>> {
+    /// Combine two witnesses into the union of their permissions.
+    ///
+    /// Both have to belong to the same [Session::user_id], see [AuthError::SessionMismatch].
+    #[allow(clippy::type_complexity)]
+    pub fn union<E: Flag, F: Flag, G: Flag, H: Flag>(
+        self,
+        other: Capability<Permissions<E, F, G, H>>,
+    ) -> Result<Capability<Permissions<A::Or<E>, B::Or<F>, C::Or<G>, D::Or<H>>>, AuthError> {
+        if self.user_id == other.user_id {
+            Ok(Certified::new(self.into_inner(), Permissions(PhantomData)))
+        } else {
+            Err(AuthError::SessionMismatch {
+                user_id: self.user_id,
+                other_user_id: other.user_id,
+            })
+        }
+    }
+}
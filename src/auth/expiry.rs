@@ -0,0 +1,215 @@
+//! Expiring and revocable witnesses.
+//!
+//! An [Authenticated] witness is valid forever once constructed.
+//! Long-lived request handlers would keep using a stale admin proof.
+//!
+//! [Expiring] records when the witness was issued and its time to live.
+//! The witness is only accessible until it expires, [Expiring::recheck] consumes it
+//! and re-authenticates the [Session] to issue a fresh one.
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::expiry::*;
+//! #
+//! let authenticator = Revocable::new(DefaultAuthenticator);
+//! let admin = authenticator
+//!     .issue::<Admin>(Session { user_id: 0 }, &(), Duration::from_secs(60))
+//!     .unwrap();
+//! assert_eq!(get_admin_page(admin.witness().unwrap()), "<html>admin</html>");
+//!
+//! // still valid, issue a fresh witness
+//! let admin = admin.recheck(&authenticator, &()).unwrap();
+//!
+//! // the witness is bound to the revocation list of its authenticator
+//! authenticator.revoke(0);
+//! assert!(matches!(admin.witness(), Err(AuthError::Revoked)));
+//! // even when rechecked with another authenticator
+//! assert!(matches!(admin.recheck(&DefaultAuthenticator, &()), Err(AuthError::Revoked)));
+//! ```
+//!
+//! A role which is no longer granted reports why, it is not a revocation.
+//! ```
+//! # use std::time::Duration;
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::expiry::*;
+//! # use bear_witness::auth::policy::*;
+//! #
+//! let auth = authenticate(Session { user_id: 0 });
+//! let admin = Expiring::<Admin>::issue(auth, Duration::from_secs(60)).unwrap();
+//! let users: StaticUsers = [(0, Role::Moderator)].into_iter().collect();
+//! assert_eq!(
+//!     admin.recheck(&users, &()).err(),
+//!     Some(AuthError::Forbidden { required_role: Role::Admin, actual_role: Role::Moderator }),
+//! );
+//! ```
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::expiry::*;
+//! #
+//! let auth = authenticate(Session { user_id: 0 });
+//! let admin = Expiring::<Admin>::issue(auth, Duration::ZERO).unwrap();
+//! assert!(matches!(admin.witness(), Err(AuthError::Expired)));
+//! assert!(matches!(admin.recheck(&DefaultAuthenticator, &()), Err(AuthError::Expired)));
+//! ```
+
+use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use super::{Auth, AuthError, Authenticated, Authenticator, Role, Session, TypedRole};
+
+/// An [Authenticated] witness with a time to live.
+///
+/// Witnesses issued by [Revocable::issue] are bound to its [RevocationList].
+pub struct Expiring<R> {
+    witness: Authenticated<R>,
+    issued_at: Instant,
+    ttl: Duration,
+    revocations: Option<RevocationList>,
+}
+impl<R: TypedRole> Expiring<R> {
+    /// Issue an expiring witness of at least the typed role `R`, starting now.
+    pub fn issue(auth: Auth<Session>, ttl: Duration) -> Result<Self, AuthError> {
+        let witness = auth.require::<R>()?;
+        Ok(Self {
+            witness,
+            issued_at: Instant::now(),
+            ttl,
+            revocations: None,
+        })
+    }
+
+    /// When was the witness issued.
+    pub fn issued_at(&self) -> Instant {
+        self.issued_at
+    }
+
+    /// Time to live of the witness.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Has the time to live elapsed?
+    pub fn is_expired(&self) -> bool {
+        self.issued_at.elapsed() >= self.ttl
+    }
+
+    /// Has the user been revoked since the witness was issued?
+    pub fn is_revoked(&self) -> bool {
+        self.revocations
+            .as_ref()
+            .is_some_and(|revocations| revocations.is_revoked(self.witness.user_id))
+    }
+
+    /// Access the witness, unless it has expired or has been revoked.
+    pub fn witness(&self) -> Result<&Authenticated<R>, AuthError> {
+        if self.is_expired() {
+            Err(AuthError::Expired)
+        } else if self.is_revoked() {
+            Err(AuthError::Revoked)
+        } else {
+            Ok(&self.witness)
+        }
+    }
+
+    /// Consume the witness and re-authenticate the [Session], issuing a fresh witness
+    /// with the same time to live, bound to the same [RevocationList].
+    ///
+    /// Fails with [AuthError::Revoked] if the user is on the [RevocationList],
+    /// otherwise with the error of [Auth::require] if the role is no longer granted.
+    pub fn recheck<A: Authenticator + ?Sized>(
+        self,
+        authenticator: &A,
+        credentials: &A::Credentials,
+    ) -> Result<Self, AuthError> {
+        self.witness()?;
+        let auth = authenticator.authenticate(self.witness.into_inner(), credentials);
+        let fresh = Self::issue(auth, self.ttl)?;
+        Ok(Self {
+            revocations: self.revocations,
+            ..fresh
+        })
+    }
+}
+
+/// Shared set of revoked [Session::user_id]s.
+///
+/// Clones share the same set, a revocation is seen by every [Expiring] witness bound to it.
+#[derive(Debug, Default, Clone)]
+pub struct RevocationList {
+    revoked: Arc<RwLock<HashSet<u32>>>,
+}
+impl RevocationList {
+    /// Revoke all witnesses of the user.
+    pub fn revoke(&self, user_id: u32) {
+        let mut revoked = self.revoked.write().unwrap_or_else(PoisonError::into_inner);
+        revoked.insert(user_id);
+    }
+
+    /// Lift the revocation.
+    pub fn restore(&self, user_id: u32) {
+        let mut revoked = self.revoked.write().unwrap_or_else(PoisonError::into_inner);
+        revoked.remove(&user_id);
+    }
+
+    /// Has the user been revoked?
+    pub fn is_revoked(&self, user_id: u32) -> bool {
+        let revoked = self.revoked.read().unwrap_or_else(PoisonError::into_inner);
+        revoked.contains(&user_id)
+    }
+}
+
+/// [Authenticator] consulting a [RevocationList], revoked users are [Role::Anonymous].
+pub struct Revocable<A> {
+    authenticator: A,
+    revocations: RevocationList,
+}
+impl<A> Revocable<A> {
+    /// Wrap the `authenticator` with an empty [RevocationList].
+    pub fn new(authenticator: A) -> Self {
+        Self {
+            authenticator,
+            revocations: RevocationList::default(),
+        }
+    }
+
+    /// Revoke all witnesses of the user.
+    pub fn revoke(&self, user_id: u32) {
+        self.revocations.revoke(user_id)
+    }
+
+    /// The [RevocationList] consulted.
+    pub fn revocations(&self) -> &RevocationList {
+        &self.revocations
+    }
+}
+impl<A: Authenticator> Revocable<A> {
+    /// Authenticate the [Session] and issue an [Expiring] witness of at least the typed role `R`,
+    /// bound to the [RevocationList].
+    pub fn issue<R: TypedRole>(
+        &self,
+        session: Session,
+        credentials: &A::Credentials,
+        ttl: Duration,
+    ) -> Result<Expiring<R>, AuthError> {
+        let auth = self.authenticate(session, credentials);
+        Ok(Expiring {
+            revocations: Some(self.revocations.clone()),
+            ..Expiring::issue(auth, ttl)?
+        })
+    }
+}
+impl<A: Authenticator> Authenticator for Revocable<A> {
+    type Credentials = A::Credentials;
+
+    fn role(&self, session: &Session, credentials: &A::Credentials) -> Role {
+        if self.revocations.is_revoked(session.user_id) {
+            Role::Anonymous
+        } else {
+            self.authenticator.role(session, credentials)
+        }
+    }
+}
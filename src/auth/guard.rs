@@ -0,0 +1,196 @@
+//! Route guards, resolving the witness a route needs before dispatching.
+//!
+//! Routes declare the witness they need as the argument of their handler.
+//! The [Router] resolves it from the [Request] with a [Guard], failures are
+//! mapped to a typed [Rejection].
+//!
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::guard::*;
+//! #
+//! let router = Router::new(DefaultAuthenticator)
+//!     .route("/", |_: ()| "<html>home</html>".to_string())
+//!     .route("/admin", |admin: Authenticated<Admin>| get_admin_page(&admin))
+//!     .route("/profile", |user: Authenticated<User>| format!("<html>{}</html>", user.user_id));
+//!
+//! let request = |path: &str, session| Request { path: path.to_string(), session, credentials: &() };
+//!
+//! assert_eq!(router.dispatch(request("/", None)), Ok("<html>home</html>".to_string()));
+//! assert_eq!(
+//!     router.dispatch(request("/admin", Some(Session { user_id: 0 }))),
+//!     Ok("<html>admin</html>".to_string()),
+//! );
+//! assert_eq!(
+//!     router.dispatch(request("/profile", Some(Session { user_id: 0 }))),
+//!     Ok("<html>0</html>".to_string()),
+//! );
+//!
+//! let rejection = router.dispatch(request("/admin", None)).unwrap_err();
+//! assert_eq!(rejection, Rejection::Auth(AuthError::Unauthenticated));
+//! assert_eq!(rejection.status(), 401);
+//! let rejection = router.dispatch(request("/admin", Some(Session { user_id: 1000 }))).unwrap_err();
+//! assert_eq!(
+//!     rejection,
+//!     Rejection::Auth(AuthError::Forbidden { required_role: Role::Admin, actual_role: Role::User }),
+//! );
+//! assert_eq!(rejection.status(), 403);
+//! assert_eq!(rejection.to_string(), "403 forbidden: requires Admin, got User");
+//! assert_eq!(router.dispatch(request("/missing", None)), Err(Rejection::NotFound));
+//! ```
+//!
+//! The credentials are borrowed, so authenticators taking unsized credentials can guard routes.
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::guard::*;
+//! # use bear_witness::auth::policy::*;
+//! #
+//! let mut verifier = PasswordVerifier::new(|password: &str| password.chars().rev().collect());
+//! verifier.register(0, "hunter2", Role::Admin);
+//! let router = Router::new(verifier)
+//!     .route("/admin", |admin: Authenticated<Admin>| get_admin_page(&admin));
+//!
+//! let request = |password| Request {
+//!     path: "/admin".to_string(),
+//!     session: Some(Session { user_id: 0 }),
+//!     credentials: password,
+//! };
+//! assert_eq!(router.dispatch(request("hunter2")), Ok("<html>admin</html>".to_string()));
+//! assert_eq!(
+//!     router.dispatch(request("*******")),
+//!     Err(Rejection::Auth(AuthError::Unauthenticated)),
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{Auth, AuthError, Authenticated, Authenticator, Session, TypedRole};
+
+/// A framework-agnostic request.
+pub struct Request<'a, C: ?Sized = ()> {
+    /// Requested path.
+    pub path: String,
+    /// Current session, [None] if there is none.
+    pub session: Option<Session>,
+    /// Credentials for the [Authenticator], borrowed so they can be unsized, e.g. a password `str`.
+    pub credentials: &'a C,
+}
+
+/// Typed rejection of a [Request].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// The [Guard] could not resolve the witness.
+    Auth(AuthError),
+    /// 404, there is no such route.
+    NotFound,
+}
+impl Rejection {
+    /// HTTP status code.
+    ///
+    /// 401 if the session is not authenticated or no longer valid,
+    /// 403 if the role or the permissions are insufficient.
+    pub fn status(&self) -> u16 {
+        match self {
+            Rejection::Auth(
+                AuthError::Forbidden { .. }
+                | AuthError::PermissionDenied { .. }
+                | AuthError::SessionMismatch { .. },
+            ) => 403,
+            Rejection::Auth(_) => 401,
+            Rejection::NotFound => 404,
+        }
+    }
+}
+impl From<AuthError> for Rejection {
+    fn from(error: AuthError) -> Self {
+        Rejection::Auth(error)
+    }
+}
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Auth(error) => write!(f, "{} {}", self.status(), error),
+            Rejection::NotFound => write!(f, "404 not found"),
+        }
+    }
+}
+impl std::error::Error for Rejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Rejection::Auth(error) => Some(error),
+            Rejection::NotFound => None,
+        }
+    }
+}
+
+/// A witness resolved from a [Request].
+pub trait Guard: Sized {
+    /// Resolve the witness, or fail with the [AuthError].
+    fn resolve<A: Authenticator>(
+        request: Request<'_, A::Credentials>,
+        authenticator: &A,
+    ) -> Result<Self, AuthError>;
+}
+
+/// Public routes, no witness needed.
+impl Guard for () {
+    fn resolve<A: Authenticator>(
+        _request: Request<'_, A::Credentials>,
+        _authenticator: &A,
+    ) -> Result<Self, AuthError> {
+        Ok(())
+    }
+}
+
+/// Any authenticated session, including [Role::Anonymous](super::Role::Anonymous).
+impl Guard for Auth<Session> {
+    fn resolve<A: Authenticator>(
+        request: Request<'_, A::Credentials>,
+        authenticator: &A,
+    ) -> Result<Self, AuthError> {
+        let session = request.session.ok_or(AuthError::Unauthenticated)?;
+        Ok(authenticator.authenticate(session, request.credentials))
+    }
+}
+
+/// A session with at least the typed role `R`.
+impl<R: TypedRole> Guard for Authenticated<R> {
+    fn resolve<A: Authenticator>(
+        request: Request<'_, A::Credentials>,
+        authenticator: &A,
+    ) -> Result<Self, AuthError> {
+        Auth::resolve(request, authenticator)?.require::<R>()
+    }
+}
+
+type Route<A, C> = Box<dyn Fn(Request<'_, C>, &A) -> Result<String, Rejection>>;
+
+/// Dispatch [Request]s to routes, resolving the [Guard] each route needs.
+pub struct Router<A: Authenticator> {
+    authenticator: A,
+    routes: HashMap<String, Route<A, A::Credentials>>,
+}
+impl<A: Authenticator> Router<A> {
+    /// Create a [Router] without any routes.
+    pub fn new(authenticator: A) -> Self {
+        Self {
+            authenticator,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Add a route, the handler argument declares the witness it needs.
+    pub fn route<G: Guard>(mut self, path: &str, handler: impl Fn(G) -> String + 'static) -> Self {
+        let route = move |request: Request<'_, A::Credentials>, authenticator: &A| {
+            Ok(handler(G::resolve(request, authenticator)?))
+        };
+        self.routes.insert(path.to_string(), Box::new(route));
+        self
+    }
+
+    /// Resolve the witness the route needs and run its handler.
+    pub fn dispatch(&self, request: Request<'_, A::Credentials>) -> Result<String, Rejection> {
+        let route = self.routes.get(&request.path).ok_or(Rejection::NotFound)?;
+        route(request, &self.authenticator)
+    }
+}
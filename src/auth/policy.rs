@@ -0,0 +1,101 @@
+//! Ready-made [Authenticator]s.
+//!
+//! ```
+//! # use bear_witness::auth::*;
+//! # use bear_witness::auth::policy::*;
+//! #
+//! // static user table
+//! let users: StaticUsers = [(0, Role::Admin), (7, Role::Moderator)].into_iter().collect();
+//! assert!(matches!(users.authenticate(Session { user_id: 7 }, &()), Auth::Moderator(_)));
+//! assert!(matches!(users.authenticate(Session { user_id: 8 }, &()), Auth::Anonymous(_)));
+//!
+//! // role lookup closure
+//! let lookup = RoleLookup(|session: &Session| {
+//!     if session.user_id < 100 { Role::Moderator } else { Role::User }
+//! });
+//! assert!(matches!(lookup.authenticate(Session { user_id: 42 }, &()), Auth::Moderator(_)));
+//!
+//! // password hash verifier, plug in a real password hashing function
+//! let mut verifier = PasswordVerifier::new(|password: &str| password.chars().rev().collect());
+//! verifier.register(0, "hunter2", Role::Admin);
+//! assert!(matches!(verifier.authenticate(Session { user_id: 0 }, "hunter2"), Auth::Admin(_)));
+//! assert!(matches!(verifier.authenticate(Session { user_id: 0 }, "*******"), Auth::Anonymous(_)));
+//! ```
+
+use std::collections::HashMap;
+
+use super::{Authenticator, Role, Session};
+
+/// Static table of [Session::user_id] -> [Role], unknown users are [Role::Anonymous].
+pub struct StaticUsers {
+    users: HashMap<u32, Role>,
+}
+impl FromIterator<(u32, Role)> for StaticUsers {
+    fn from_iter<I: IntoIterator<Item = (u32, Role)>>(iter: I) -> Self {
+        Self {
+            users: iter.into_iter().collect(),
+        }
+    }
+}
+impl Authenticator for StaticUsers {
+    type Credentials = ();
+
+    fn role(&self, session: &Session, _credentials: &()) -> Role {
+        self.users
+            .get(&session.user_id)
+            .copied()
+            .unwrap_or(Role::Anonymous)
+    }
+}
+
+/// Decide the [Role] with a closure.
+pub struct RoleLookup<F>(pub F);
+impl<F> Authenticator for RoleLookup<F>
+where
+    F: Fn(&Session) -> Role,
+{
+    type Credentials = ();
+
+    fn role(&self, session: &Session, _credentials: &()) -> Role {
+        (self.0)(session)
+    }
+}
+
+/// Verify a password against the stored hash, a wrong password is [Role::Anonymous].
+///
+/// The hashing function `H` is pluggable, this crate does not ship one.
+pub struct PasswordVerifier<H> {
+    hash: H,
+    users: HashMap<u32, (String, Role)>,
+}
+impl<H> PasswordVerifier<H>
+where
+    H: Fn(&str) -> String,
+{
+    /// Create an empty [PasswordVerifier] using the `hash` function.
+    pub fn new(hash: H) -> Self {
+        Self {
+            hash,
+            users: HashMap::new(),
+        }
+    }
+
+    /// Register a user, only the hash of the `password` is stored.
+    pub fn register(&mut self, user_id: u32, password: &str, role: Role) {
+        let hash = (self.hash)(password);
+        self.users.insert(user_id, (hash, role));
+    }
+}
+impl<H> Authenticator for PasswordVerifier<H>
+where
+    H: Fn(&str) -> String,
+{
+    type Credentials = str;
+
+    fn role(&self, session: &Session, password: &str) -> Role {
+        match self.users.get(&session.user_id) {
+            Some((hash, role)) if *hash == (self.hash)(password) => *role,
+            _ => Role::Anonymous,
+        }
+    }
+}
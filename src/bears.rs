@@ -6,7 +6,7 @@
 //! # use bear_witness::bears::*;
 //! #
 //! let animal = BrownBear;
-//! let certified_bear = bear_witness(animal); // -> Certified<BrownBear>
+//! let certified_bear = bear_witness(animal); // -> Certified<BrownBear, ProvenBear>
 //! // we can call [Bear#growl]
 //! println!("{}", certified_bear.growl());
 //! // we can also call [BrownBear#do_brown_bear_things],
@@ -14,7 +14,7 @@
 //! println!("{}", certified_bear.do_brown_bear_things());
 //!
 //! let animal = PolarBear;
-//! let certified_bear = bear_witness(animal); // -> Certified<PolarBear>
+//! let certified_bear = bear_witness(animal); // -> Certified<PolarBear, ProvenBear>
 //! // we can call [Bear#growl]
 //! println!("{}", certified_bear.growl());
 //! // cannot call [BrownBear#do_brown_bear_things], because this is still a [PolarBear]
@@ -38,11 +38,16 @@ use crate::Certified;
 /// Type check a trait bound without erasing type information.
 ///
 /// We wrap the return value in [Certified] to signify that
-/// this value has been type-checked.
-pub fn bear_witness<T: Bear>(bear: T) -> Certified<T> {
-    Certified::new(bear)
+/// this value has been type-checked, tagged with [ProvenBear].
+pub fn bear_witness<T: Bear>(bear: T) -> Certified<T, ProvenBear> {
+    Certified::new(bear, ProvenBear(()))
 }
 
+/// Proof marker for [bear_witness], the value has been type-checked to impl [Bear].
+///
+/// The private field ensures only this module can construct it.
+pub struct ProvenBear(());
+
 // Define the [Bear] trait.
 
 /// [Bear] trait, implemented on [BrownBear] and [PolarBear], but not on [Dog].
@@ -49,22 +49,31 @@
 //! ```
 //! > sidenote: once `const generics` work over enums, we won't need this
 //!
-//! Define [Localized] wrapper for a value.
+//! Define [Localized] wrapper for a value, carrying the language as a type parameter.
 //! ```
 //! # use bear_witness::i18n::*;
 //! #
-//! enum Localized<T> {
-//!     English(T),
-//!     French(T),
-//!     German(T),
+//! struct Localized<T, L: TypedLang> {
+//!     value: T,
+//!     lang: L,
 //! }
 //!
 //! trait Localize<L: TypedLang> {
-//!     fn localize(self, lang: L) -> Localized<Self>
-//!         where Self: Sized;
+//!     fn translate(&self, lang: &L) -> String;
+//!
+//!     fn localize(self, lang: L) -> Localized<Self, L>
+//!         where Self: Sized
+//!     {
+//!         Localized { value: self, lang }
+//!     }
 //! }
 //! ```
 //!
+//! A [Localized] value can only be constructed by [Localize::localize],
+//! so it is a type witness that the translation exists.
+//! The [Render] trait dispatches the rendering statically on the language type parameter,
+//! there is no runtime enum to match on anymore.
+//!
 //! ## Wire it all together
 //!
 //! Impl [Localize] for languages we support.
@@ -75,13 +84,13 @@
 //! #     pub who: String,
 //! # }
 //! impl Localize<English> for Context {
-//!     fn localize(self, _lang: English) -> Localized<Self> {
-//!         Localized::English(self)
+//!     fn translate(&self, _lang: &English) -> String {
+//!         format!("Hello {}", self.who)
 //!     }
 //! }
 //! impl Localize<German> for Context {
-//!     fn localize(self, _lang: German) -> Localized<Self> {
-//!         Localized::German(self)
+//!     fn translate(&self, _lang: &German) -> String {
+//!         format!("Hallo {}", self.who)
 //!     }
 //! }
 //! ```
@@ -92,90 +101,481 @@
 //! ```
 //! # use bear_witness::i18n::*;
 //! #
-//! fn render(localized: Localized<Context>) -> String {
-//!     match localized {
-//!         Localized::English(context) => format!("Hello {}", context.who),
-//!         Localized::French(context) => format!("Bonjour {}", context.who),
-//!         _ => unimplemented!(),
+//! let context = Context { who: "World".to_string() };
+//! assert_eq!(render(context.localize(English)), "Hello World");
+//! ```
+//!
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! # let context = Context { who: "World".to_string() };
+//! render(context.localize(German));
+//! // error: the trait `Localize<German>` is not implemented for `Context`
+//! ```
+//!
+//! ## Generating translations
+//!
+//! Writing a [Localize] impl by hand for every language is repetitive,
+//! the [translations!](crate::translations!) macro generates them from a table of templates.
+//! ```
+//! # use bear_witness::i18n::*;
+//! #
+//! struct Farewell {
+//!     who: String,
+//!     when: String,
+//! }
+//! bear_witness::translations! {
+//!     Farewell { who, when } {
+//!         English => "Goodbye {who}, see you {when}",
+//!         German => "Auf Wiedersehen {who}, bis {when}",
 //!     }
 //! }
 //!
+//! let farewell = Farewell { who: "World".to_string(), when: "soon".to_string() };
+//! assert_eq!(render(farewell.localize(German)), "Auf Wiedersehen World, bis soon");
+//! ```
+//!
+//! Messages declaring their arguments with types are defined by [message!](crate::message!),
+//! see [message].
+//!
+//! ## Runtime languages
+//!
+//! Requests arrive with a runtime [Language], e.g. from the `Accept-Language` header,
+//! see [negotiate].
+//! [dispatch] picks the matching [TypedLang] from the [Translations] of the type,
+//! without writing the match by hand.
+//! ```
+//! # use bear_witness::i18n::*;
+//! #
 //! let context = Context { who: "World".to_string() };
-//! assert_eq!(render(context.localize(English)), "Hello World");
+//! assert_eq!(dispatch(&context, Language::French), Ok("Bonjour World".to_string()));
+//! assert_eq!(dispatch(&context, Language::German), Err(Unsupported(Language::German)));
+//! assert_eq!(Context::languages(), vec![Language::English, Language::French, Language::Pseudo]);
 //! ```
 //!
+//! The [Translations] can only list languages the type implements [Localize] for,
+//! [coverage] reports them for all the message types.
 //! ```compile_fail
 //! # use bear_witness::i18n::*;
 //! #
-//! # fn render(localized: Localized<Context>) -> String {
-//! #     match localized {
-//! #         Localized::English(context) => format!("Hello {}", context.who),
-//! #         Localized::French(context) => format!("Bonjour {}", context.who),
-//! #         _ => unimplemented!(),
-//! #     }
-//! # }
+//! struct Farewell;
+//! impl Localize<English> for Farewell {
+//!     fn translate(&self, _lang: &English) -> String {
+//!         "Goodbye".to_string()
+//!     }
+//! }
+//! impl Translations for Farewell {
+//!     type Languages = (English, (German, ()));
+//!     // error: the trait `Localize<German>` is not implemented for `Farewell`
+//! }
+//! ```
+//!
+//! Messages with counts and genders select their forms with the language rules, see [plural].
+//! Numbers, dates and amounts follow the language conventions, see [format](mod@format).
+//! Values interpolated into right-to-left messages are isolated, see [bidi].
+//! Layouts can be tested without real translations, see [pseudo].
+//!
+//! When a translation is missing, a chain of fallback languages can be resolved
+//! at compile time instead, see [fallback].
+//! Regional variants fall back to their base language, see [region].
+//!
+//! Translations delivered as Fluent or gettext files can be turned into
+//! [translations!](crate::translations!) invocations at build time, see [catalogue].
+//!
+//! A [Localized] value cannot be forged either, skipping the [Localize] impl.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
 //! #
 //! # let context = Context { who: "World".to_string() };
-//! render(context.localize(German));
-//! // error: the trait `Localize<German>` is not implemented for `Context`
+//! let localized = Localized { value: context, lang: German };
+//! // error: cannot construct `Localized<_, _>` with struct literal syntax due to private fields
 //! ```
 
+pub mod bidi;
+pub mod catalogue;
+pub mod coverage;
+pub mod fallback;
+pub mod format;
+pub mod message;
+pub mod negotiate;
+pub mod plural;
+pub mod pseudo;
+pub mod region;
+
 /// The context for rendering localized message.
 pub struct Context {
     /// Who do we want to greet?
     pub who: String,
 }
 
-/// Language enum.
-pub enum Language {
-    /// English
-    English,
-    /// French
-    French,
-    /// German
-    German,
+/// The context for rendering a message with a count, see [plural].
+pub struct Inbox {
+    /// Whose inbox is it?
+    pub who: String,
+    /// How many messages are unread?
+    pub unread: u64,
+}
+
+/// The context for rendering a message with a grammatical gender, see [plural].
+pub struct Welcome {
+    /// Who do we welcome?
+    pub who: String,
+    /// The gender of `who`.
+    pub gender: plural::Gender,
+}
+
+/// Define the [Language] enum and a [TypedLang] for each of its variants,
+/// regional variants name their base language.
+/// Pseudo-locales come last, they are kept out of [Language::ALL].
+///
+/// Adding a language is a single line in the invocation below.
+macro_rules! languages {
+    (
+        $($lang:ident = $code:literal $(: $base:ident)?),* $(,)?
+        ; pseudo: $($pseudo:ident = $pseudo_code:literal),* $(,)?
+    ) => {
+        /// Language enum.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Language {
+            $(
+                #[doc = stringify!($lang)]
+                $lang,
+            )*
+            $(
+                #[doc = concat!(stringify!($pseudo), ", a pseudo-locale, see [pseudo].")]
+                $pseudo,
+            )*
+        }
+        impl Language {
+            /// All the real languages, the pseudo-locales are not offered to users.
+            pub const ALL: &'static [Language] = &[$(Language::$lang),*];
+            /// The pseudo-locales, for testing, see [pseudo].
+            pub const PSEUDO: &'static [Language] = &[$(Language::$pseudo),*];
+
+            /// BCP-47 language tag.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $(Language::$lang => $code,)*
+                    $(Language::$pseudo => $pseudo_code,)*
+                }
+            }
+
+            /// Parse a BCP-47 language tag, exactly as returned by [Language::code].
+            ///
+            /// See [LanguageTag](region::LanguageTag) to parse any well-formed tag.
+            pub fn from_code(code: &str) -> Option<Language> {
+                match code {
+                    $($code => Some(Language::$lang),)*
+                    $($pseudo_code => Some(Language::$pseudo),)*
+                    _ => None,
+                }
+            }
+
+            /// The base language of a regional variant, the language itself otherwise.
+            pub const fn base(&self) -> Language {
+                match self {
+                    $(Language::$lang => languages!(@base $lang $($base)?),)*
+                    $(Language::$pseudo => Language::$pseudo,)*
+                }
+            }
+
+            /// Is this a pseudo-locale, in [Language::PSEUDO]?
+            pub const fn is_pseudo(&self) -> bool {
+                matches!(self, $(Language::$pseudo)|*)
+            }
+        }
+
+        $($(
+            impl region::Regional for $lang {
+                type Base = $base;
+            }
+        )?)*
+
+        languages!(@typed fallback::Zero; $($lang,)* $($pseudo),*);
+    };
+    (@base $lang:ident) => { Language::$lang };
+    (@base $lang:ident $base:ident) => { Language::$base };
+    (@typed $id:ty;) => {};
+    (@typed $id:ty; $lang:ident $(, $rest:ident)* $(,)?) => {
+        #[doc = concat!("Typed ", stringify!($lang))]
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct $lang;
+        impl TypedLang for $lang {
+            const LANGUAGE: Language = Language::$lang;
+            type Id = $id;
+        }
+        languages!(@typed fallback::Succ<$id>; $($rest),*);
+    };
 }
 
 /// Typed language trait, so we can pass a typed language to functions.
-pub trait TypedLang {}
-
-/// Typed English
-pub struct English;
-impl TypedLang for English {}
-/// Typed French
-pub struct French;
-impl TypedLang for French {}
-/// Typed German
-pub struct German;
-impl TypedLang for German {}
-
-/// Localized value into a language.
-pub enum Localized<T: Sized> {
-    /// English
-    English(T),
-    /// French
-    French(T),
-    /// German
-    German(T),
+pub trait TypedLang {
+    /// The [Language] value of this type.
+    const LANGUAGE: Language;
+    /// Type-level identity, to compare languages in [fallback] chains.
+    type Id;
+    /// Direction of the script, see [bidi].
+    const DIRECTION: bidi::Direction = Self::LANGUAGE.direction();
+}
+
+languages! {
+    English = "en",
+    French = "fr",
+    German = "de",
+    Arabic = "ar",
+    Hebrew = "he",
+    EnglishUS = "en-US": English,
+    EnglishGB = "en-GB": English,
+    FrenchCA = "fr-CA": French,
+    GermanCH = "de-CH": German;
+    pseudo:
+    Pseudo = "en-XA",
+}
+
+/// Value localized into the language `L`.
+///
+/// Only [Localize::localize] can construct it.
+pub struct Localized<T, L: TypedLang> {
+    value: T,
+    lang: L,
+}
+impl<T, L: TypedLang> Localized<T, L> {
+    /// The typed language.
+    pub fn lang(&self) -> &L {
+        &self.lang
+    }
+
+    /// Drop the localization, returning the inner value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
 }
 
 /// Localize -> [Localized]
 pub trait Localize<L: TypedLang> {
+    /// Translate the value into the language `L`.
+    fn translate(&self, lang: &L) -> String;
+
     /// Turn a value into [Localized] for a given language.
-    fn localize(self, lang: L) -> Localized<Self>
+    fn localize(self, lang: L) -> Localized<Self, L>
     where
-        Self: Sized;
+        Self: Sized,
+    {
+        Localized { value: self, lang }
+    }
 }
 
-// impl Localize for Context
+/// Render a value in the language `L`, dispatched statically.
+pub trait Render<L: TypedLang> {
+    /// Render the value.
+    fn render(&self) -> String;
+}
+impl<T: Localize<L>, L: TypedLang> Render<L> for Localized<T, L> {
+    fn render(&self) -> String {
+        self.value.translate(&self.lang)
+    }
+}
+
+/// Render a [Localized] value.
+pub fn render<L: TypedLang>(localized: impl Render<L>) -> String {
+    localized.render()
+}
+
+/// The set of languages a type implements [Localize] for.
+///
+/// Generated by [translations!](crate::translations!).
+pub trait Translations {
+    /// Type-level list of [TypedLang]s, e.g. `(English, (French, ()))`.
+    type Languages: LanguageSet<Self>;
 
-impl Localize<English> for Context {
-    fn localize(self, _lang: English) -> Localized<Self> {
-        Localized::English(self)
+    /// The supported [Language] values.
+    fn languages() -> Vec<Language> {
+        Self::Languages::languages()
     }
 }
-impl Localize<French> for Context {
-    fn localize(self, _lang: French) -> Localized<Self> {
-        Localized::French(self)
+
+/// Type-level list of [TypedLang]s the type `T` implements [Localize] for.
+pub trait LanguageSet<T: ?Sized> {
+    /// The [Language] values in the set.
+    fn languages() -> Vec<Language>;
+
+    /// Translate the value if the language is in the set.
+    fn translate(value: &T, language: Language) -> Option<String>;
+}
+impl<T: ?Sized> LanguageSet<T> for () {
+    fn languages() -> Vec<Language> {
+        Vec::new()
+    }
+
+    fn translate(_value: &T, _language: Language) -> Option<String> {
+        None
+    }
+}
+impl<T, L, Rest> LanguageSet<T> for (L, Rest)
+where
+    T: Localize<L> + ?Sized,
+    L: TypedLang + Default,
+    Rest: LanguageSet<T>,
+{
+    fn languages() -> Vec<Language> {
+        let mut languages = vec![L::LANGUAGE];
+        languages.extend(Rest::languages());
+        languages
+    }
+
+    fn translate(value: &T, language: Language) -> Option<String> {
+        if language == L::LANGUAGE {
+            Some(value.translate(&L::default()))
+        } else {
+            Rest::translate(value, language)
+        }
+    }
+}
+
+/// The runtime [Language] is not in the [Translations] of the type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsupported(pub Language);
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unsupported language {:?}", self.0)
+    }
+}
+impl std::error::Error for Unsupported {}
+
+/// Render the value in a runtime [Language], dispatching to the matching [TypedLang].
+///
+/// A regional variant missing from the [Translations] falls back to its [Language::base],
+/// like [localize_region](region::LocalizeRegion::localize_region).
+pub fn dispatch<T: Translations>(value: &T, language: Language) -> Result<String, Unsupported> {
+    T::Languages::translate(value, language)
+        .or_else(|| T::Languages::translate(value, language.base()))
+        .ok_or(Unsupported(language))
+}
+
+/// Generate [Localize] impls for a context type from a table of message templates,
+/// and its [Translations].
+///
+/// The listed fields of the context are the arguments of the templates,
+/// every template has to use all of them and nothing else, or the compilation fails.
+/// They are [isolated](bidi::Bidi::isolated) in the direction of the language.
+///
+/// A message depending on a count or a [Gender](plural::Gender) field lists a template
+/// for each form the language requires, [Plural::Forms](plural::Plural::Forms) or
+/// [Gendered::Forms](plural::Gendered::Forms). A missing form fails the compilation,
+/// the forms may use any of the fields.
+///
+/// An [English] template also translates the type into the [Pseudo] locale,
+/// see the [pseudo] module.
+///
+/// ```text
+/// translations! {
+///     <Context type> { <field>, ... } {
+///         <TypedLang> => "<template>",
+///         <TypedLang> => plural(<count field>) { <form>: "<template>", ... },
+///         <TypedLang> => gender(<gender field>) { <form>: "<template>", ... },
+///         ...
+///     }
+/// }
+/// ```
+///
+/// See the [i18n](crate::i18n) and [plural] modules for examples.
+#[macro_export]
+macro_rules! translations {
+    (@format $self:ident $lang:ident: $lang_ty:ty, $context:ident { $($field:ident),* $(,)? }, $wrap:path;
+        $template:literal
+    ) => {{
+        let _ = $lang;
+        let $context { $($field,)* .. } = $self;
+        $(let $field = $wrap($lang, $field);)*
+        format!($template, $($field = $field),*)
+    }};
+    (@format $self:ident $lang:ident: $lang_ty:ty, $context:ident { $($field:ident),* $(,)? }, $wrap:path;
+        plural($count:ident) { $($form:ident: $template:literal),* $(,)? }
+    ) => {{
+        type Forms<T> = <$lang_ty as $crate::i18n::plural::Plural>::Forms<T>;
+        #[allow(unused_variables)]
+        let $context { $($field,)* .. } = $self;
+        let count = u64::from(*$count);
+        $(#[allow(unused_variables)] let $field = $wrap($lang, $field);)*
+        let forms: Forms<String> = Forms { $($form: format!($template)),* };
+        $crate::i18n::plural::Plural::plural($lang, count, forms)
+    }};
+    (@format $self:ident $lang:ident: $lang_ty:ty, $context:ident { $($field:ident),* $(,)? }, $wrap:path;
+        gender($gender:ident) { $($form:ident: $template:literal),* $(,)? }
+    ) => {{
+        type Forms<T> = <$lang_ty as $crate::i18n::plural::Gendered>::Forms<T>;
+        #[allow(unused_variables)]
+        let $context { $($field,)* .. } = $self;
+        let gender = *$gender;
+        $(#[allow(unused_variables)] let $field = $wrap($lang, $field);)*
+        let forms: Forms<String> = Forms { $($form: format!($template)),* };
+        $crate::i18n::plural::Gendered::gender($lang, gender, forms)
+    }};
+    (@impl $context:ident $fields:tt $lang:ty => $($message:tt)+) => {
+        impl $crate::i18n::Localize<$lang> for $context {
+            fn translate(&self, lang: &$lang) -> String {
+                $crate::translations!(
+                    @format self lang: $lang, $context $fields, $crate::i18n::bidi::Bidi::isolated; $($message)+
+                )
+            }
+        }
+        impl $crate::i18n::pseudo::Template<$lang> for $context {
+            fn format_arguments(&self, lang: &$lang) -> String {
+                $crate::translations!(
+                    @format self lang: $lang, $context $fields, $crate::i18n::pseudo::argument; $($message)+
+                )
+            }
+        }
+    };
+    (@list) => { () };
+    (@list $head:ty $(, $tail:ty)*) => { ($head, $crate::translations!(@list $($tail),*)) };
+    (@table $context:ident $fields:tt [$($lang:ty),*] {}) => {
+        impl $crate::i18n::Translations for $context {
+            type Languages = $crate::i18n::pseudo::WithPseudo<$crate::translations!(@list $($lang),*)>;
+        }
+    };
+    (@table $context:ident $fields:tt [$($done:ty),*] {
+        $lang:ty => $template:literal $(, $($rest:tt)*)?
+    }) => {
+        $crate::translations!(@impl $context $fields $lang => $template);
+        $crate::translations!(@table $context $fields [$($done,)* $lang] { $($($rest)*)? });
+    };
+    (@table $context:ident $fields:tt [$($done:ty),*] {
+        $lang:ty => $selector:ident($argument:ident) $forms:tt $(, $($rest:tt)*)?
+    }) => {
+        $crate::translations!(@impl $context $fields $lang => $selector($argument) $forms);
+        $crate::translations!(@table $context $fields [$($done,)* $lang] { $($($rest)*)? });
+    };
+    ($($context:ident $fields:tt $table:tt)*) => {$(
+        $crate::translations!(@table $context $fields [] $table);
+    )*};
+}
+
+// impl Localize for Context
+
+crate::translations! {
+    Context { who } {
+        English => "Hello {who}",
+        French => "Bonjour {who}",
+    }
+    Inbox { who, unread } {
+        English => plural(unread) {
+            one: "{who}, you have {unread} unread message",
+            other: "{who}, you have {unread} unread messages",
+        },
+        French => plural(unread) {
+            one: "{who}, vous avez {unread} message non lu",
+            many: "{who}, vous avez {unread} de messages non lus",
+            other: "{who}, vous avez {unread} messages non lus",
+        },
+    }
+    Welcome { who, gender } {
+        English => gender(gender) {
+            other: "Welcome {who}",
+        },
+        French => gender(gender) {
+            masculine: "Bienvenu {who}",
+            feminine: "Bienvenue {who}",
+        },
     }
 }
@@ -0,0 +1,213 @@
+//! Text direction and bidi isolation.
+//!
+//! Each typed language declares its [TypedLang::DIRECTION]. Interpolated values, e.g. a user name,
+//! may be written in the other direction and scramble the surrounding message.
+//! [Bidi::isolate] wraps them in Unicode isolation marks whenever that can happen.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::bidi::*;
+//! #
+//! struct Greeting {
+//!     who: String,
+//! }
+//! impl Localize<English> for Greeting {
+//!     fn translate(&self, lang: &English) -> String {
+//!         format!("Hello {}!", lang.isolate(&self.who))
+//!     }
+//! }
+//! impl Localize<Arabic> for Greeting {
+//!     fn translate(&self, lang: &Arabic) -> String {
+//!         format!("مرحبا {}!", lang.isolate(&self.who))
+//!     }
+//! }
+//! impl Localize<Hebrew> for Greeting {
+//!     fn translate(&self, lang: &Hebrew) -> String {
+//!         format!("שלום {}!", lang.isolate(&self.who))
+//!     }
+//! }
+//!
+//! let greeting = |who: &str| Greeting { who: who.to_string() };
+//! // left-to-right into left-to-right, nothing to isolate
+//! assert_eq!(render(greeting("World").localize(English)), "Hello World!");
+//! // right-to-left into left-to-right
+//! assert_eq!(render(greeting("سلمى").localize(English)), "Hello \u{2068}سلمى\u{2069}!");
+//! // left-to-right into right-to-left
+//! assert_eq!(render(greeting("World").localize(Arabic)), "مرحبا \u{2068}World\u{2069}!");
+//! assert_eq!(render(greeting("Dana 2").localize(Hebrew)), "שלום \u{2068}Dana 2\u{2069}!");
+//! // right-to-left into right-to-left, still isolated as the value may start with a number
+//! assert_eq!(render(greeting("3 דנה").localize(Hebrew)), "שלום \u{2068}3 דנה\u{2069}!");
+//! ```
+//!
+//! [translations!](crate::translations!) isolates every interpolated field the same way.
+//! ```
+//! # use bear_witness::i18n::*;
+//! #
+//! struct Salute {
+//!     who: String,
+//!     count: u32,
+//! }
+//! bear_witness::translations! {
+//!     Salute { who, count } {
+//!         English => "Hello {who} {count}!",
+//!         Arabic => "مرحبا {who} {count}!",
+//!     }
+//! }
+//!
+//! let context = Context { who: "سلمى".to_string() };
+//! assert_eq!(render(context.localize(English)), "Hello \u{2068}سلمى\u{2069}");
+//!
+//! let salute = || Salute { who: "World".to_string(), count: 2 };
+//! assert_eq!(render(salute().localize(English)), "Hello World 2!");
+//! assert_eq!(render(salute().localize(Arabic)), "مرحبا \u{2068}World\u{2069} \u{2068}2\u{2069}!");
+//! ```
+//!
+//! Whole messages embedded in a page of the other direction are isolated with [render_isolated].
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::bidi::*;
+//! #
+//! let context = Context { who: "World".to_string() };
+//! assert_eq!(render_isolated(context.localize(English)), "\u{2066}Hello World\u{2069}");
+//!
+//! assert_eq!(English::DIRECTION, Direction::LeftToRight);
+//! assert_eq!(Arabic::DIRECTION, Direction::RightToLeft);
+//! assert_eq!(Language::Hebrew.direction(), Direction::RightToLeft);
+//! assert_eq!(Language::EnglishGB.direction(), Direction::LeftToRight);
+//! assert_eq!(direction_of("abc אבג"), Some(Direction::LeftToRight));
+//! assert_eq!(direction_of("123 אבג"), Some(Direction::RightToLeft));
+//! assert_eq!(direction_of("123"), None);
+//! ```
+
+use std::fmt::{self, Debug, Display};
+use std::marker::PhantomData;
+
+use super::{Language, Render, TypedLang};
+
+/// Left-to-right isolate.
+pub const LRI: char = '\u{2066}';
+/// Right-to-left isolate.
+pub const RLI: char = '\u{2067}';
+/// First strong isolate, the direction is detected from the content.
+pub const FSI: char = '\u{2068}';
+/// Pop directional isolate, closes [LRI], [RLI] and [FSI].
+pub const PDI: char = '\u{2069}';
+
+/// Direction of a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Latin, ...
+    LeftToRight,
+    /// Arabic, Hebrew, ...
+    RightToLeft,
+}
+impl Direction {
+    /// The isolate mark opening text of this direction.
+    pub fn isolate(&self) -> char {
+        match self {
+            Direction::LeftToRight => LRI,
+            Direction::RightToLeft => RLI,
+        }
+    }
+}
+
+impl Language {
+    /// Direction of the script, regional variants share the direction of their base language.
+    pub const fn direction(&self) -> Direction {
+        match self.base() {
+            Language::Arabic | Language::Hebrew => Direction::RightToLeft,
+            _ => Direction::LeftToRight,
+        }
+    }
+}
+
+/// Is the character of a right-to-left script?
+fn is_right_to_left(c: char) -> bool {
+    matches!(c,
+        '\u{0590}'..='\u{08ff}'
+        | '\u{fb1d}'..='\u{fdff}'
+        | '\u{fe70}'..='\u{feff}'
+        | '\u{10800}'..='\u{10fff}'
+        | '\u{1e800}'..='\u{1efff}'
+    )
+}
+
+/// Direction of the first strong character, [None] if there are only neutral characters.
+pub fn direction_of(text: &str) -> Option<Direction> {
+    text.chars().find_map(|c| {
+        if is_right_to_left(c) {
+            Some(Direction::RightToLeft)
+        } else if c.is_alphabetic() {
+            Some(Direction::LeftToRight)
+        } else {
+            None
+        }
+    })
+}
+
+/// Does the value need isolating in a message of the direction?
+fn needs_isolation(direction: Direction, value: &str) -> bool {
+    direction == Direction::RightToLeft || value.chars().any(is_right_to_left)
+}
+
+/// Bidi-aware interpolation for typed languages.
+pub trait Bidi: TypedLang {
+    /// Format the value for interpolation into a message of this language.
+    ///
+    /// The value is wrapped in [FSI] ... [PDI] unless both the message and the value are
+    /// left-to-right. Right-to-left messages always isolate, neutral characters of the
+    /// value such as digits would otherwise be reordered with the message.
+    fn isolate(&self, value: &impl Display) -> String {
+        let value = value.to_string();
+        if needs_isolation(Self::DIRECTION, &value) {
+            format!("{}{}{}", FSI, value, PDI)
+        } else {
+            value
+        }
+    }
+
+    /// Like [Bidi::isolate], but lazily when formatted, keeping the format spec of the value.
+    fn isolated<T>(&self, value: T) -> Isolated<Self, T>
+    where
+        Self: Sized,
+    {
+        Isolated {
+            value,
+            lang: PhantomData,
+        }
+    }
+}
+impl<L: TypedLang> Bidi for L {}
+
+/// A value isolated for interpolation into a message of the language `L`, see [Bidi::isolated].
+pub struct Isolated<L, T> {
+    value: T,
+    lang: PhantomData<L>,
+}
+impl<L: TypedLang, T: Display> Display for Isolated<L, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if needs_isolation(L::DIRECTION, &self.value.to_string()) {
+            write!(f, "{}", FSI)?;
+            self.value.fmt(f)?;
+            write!(f, "{}", PDI)
+        } else {
+            self.value.fmt(f)
+        }
+    }
+}
+impl<L: TypedLang, T: Debug> Debug for Isolated<L, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if needs_isolation(L::DIRECTION, &format!("{:?}", self.value)) {
+            write!(f, "{}", FSI)?;
+            self.value.fmt(f)?;
+            write!(f, "{}", PDI)
+        } else {
+            self.value.fmt(f)
+        }
+    }
+}
+
+/// Render a [Localized](super::Localized) value isolated in the direction of its language,
+/// to embed it into text of any direction.
+pub fn render_isolated<L: TypedLang>(localized: impl Render<L>) -> String {
+    format!("{}{}{}", L::DIRECTION.isolate(), localized.render(), PDI)
+}
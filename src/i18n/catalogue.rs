@@ -0,0 +1,479 @@
+//! Translation catalogues, loaded at build time.
+//!
+//! Translators deliver Fluent (`.ftl`) and gettext (`.po`) files, one per language,
+//! named by the [Language::code]: `en.ftl`, `fr.po`, ...
+//!
+//! [Catalogue::generate] turns them into [translations!](crate::translations!) invocations
+//! for the message context types. A message missing in a language the type claims
+//! to support generates a `compile_error!`, failing the compilation.
+//!
+//! Call it from a build script and include the generated file.
+//! ```no_run
+//! // build.rs
+//! use bear_witness::i18n::catalogue::{Catalogue, Message};
+//! use bear_witness::i18n::Language;
+//!
+//! let catalogue = Catalogue::load("locales").unwrap();
+//! let code = catalogue.generate(&[Message {
+//!     context: "Greeting",
+//!     fields: &["who"],
+//!     id: "hello",
+//!     languages: &[Language::English, Language::French],
+//! }]);
+//! let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//! std::fs::write(out_dir.join("translations.rs"), code).unwrap();
+//! println!("cargo:rerun-if-changed=locales");
+//!
+//! // lib.rs
+//! // include!(concat!(env!("OUT_DIR"), "/translations.rs"));
+//! ```
+//!
+//! ```
+//! # use bear_witness::i18n::catalogue::*;
+//! # use bear_witness::i18n::Language;
+//! #
+//! let dir = std::env::temp_dir().join(format!("bear_witness_catalogue_{}", std::process::id()));
+//! std::fs::create_dir_all(&dir).unwrap();
+//! std::fs::write(dir.join("en.ftl"), "# greetings\nhello = Hello { $who }\n").unwrap();
+//! std::fs::write(dir.join("fr.po"), "msgid \"hello\"\nmsgstr \"Bonjour {who}\"\n").unwrap();
+//!
+//! let catalogue = Catalogue::load(&dir).unwrap();
+//! assert_eq!(catalogue.template(Language::English, "hello"), Some("Hello {who}"));
+//! assert_eq!(catalogue.template(Language::French, "hello"), Some("Bonjour {who}"));
+//!
+//! let message = Message {
+//!     context: "Greeting",
+//!     fields: &["who"],
+//!     id: "hello",
+//!     languages: &[Language::English, Language::French, Language::German],
+//! };
+//! let code = catalogue.generate(&[message]);
+//! assert!(code.contains(r#"::bear_witness::i18n::English => "Hello {who}","#));
+//! assert!(code.contains(r#"::bear_witness::i18n::French => "Bonjour {who}","#));
+//! // German is claimed but missing
+//! assert!(code.contains(r#"compile_error!("message `hello` of `Greeting` is missing in `de`");"#));
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+//!
+//! In `.po` files the placeholders are `{name}`, every other brace is literal text.
+//! Entries with a `msgctxt` or plural forms are skipped, so are the unreviewed `fuzzy` ones.
+//! ```
+//! # use bear_witness::i18n::catalogue::*;
+//! # use bear_witness::i18n::Language;
+//! #
+//! let dir = std::env::temp_dir().join(format!("bear_witness_catalogue_po_{}", std::process::id()));
+//! std::fs::create_dir_all(&dir).unwrap();
+//! std::fs::write(
+//!     dir.join("fr.po"),
+//!     r#"
+//! msgid ""
+//! msgstr "Content-Type: text/plain; charset=UTF-8\n"
+//!
+//! msgid "set"
+//! msgstr "{who} : {1, 2} { who } }"
+//!
+//! msgctxt "menu"
+//! msgid "open"
+//! msgstr "Ouvrir"
+//!
+//! msgid "apple"
+//! msgid_plural "apples"
+//! msgstr[0] "pomme"
+//! msgstr[1] "pommes"
+//!
+//! msgid "close"
+//! msgstr ""
+//! "Fermer"
+//!
+//! #, fuzzy, c-format
+//! msgid "bye"
+//! msgstr "Au revoir {who}"
+//! "#,
+//! )
+//! .unwrap();
+//!
+//! let catalogue = Catalogue::load(&dir).unwrap();
+//! assert_eq!(catalogue.template(Language::French, "set"), Some("{who} : {{1, 2}} {{ who }} }}"));
+//! assert_eq!(catalogue.template(Language::French, "open"), None);
+//! assert_eq!(catalogue.template(Language::French, "apple"), None);
+//! assert_eq!(catalogue.template(Language::French, "close"), Some("Fermer"));
+//! assert_eq!(catalogue.template(Language::French, "bye"), None);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+//!
+//! The [pseudo-locales](Language::PSEUDO) are generated from English, their catalogues are refused.
+//! ```
+//! # use bear_witness::i18n::catalogue::*;
+//! #
+//! let dir = std::env::temp_dir().join(format!("bear_witness_catalogue_pseudo_{}", std::process::id()));
+//! std::fs::create_dir_all(&dir).unwrap();
+//! std::fs::write(dir.join("en-XA.po"), "msgid \"hello\"\nmsgstr \"[Ĥéļļö {who}]\"\n").unwrap();
+//!
+//! let error = Catalogue::load(&dir).unwrap_err();
+//! assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::Language;
+
+/// Message templates for each [Language], by message id.
+#[derive(Debug, Default)]
+pub struct Catalogue {
+    templates: HashMap<Language, HashMap<String, String>>,
+}
+
+/// A message context type, to generate [Localize](super::Localize) impls for.
+#[derive(Debug, Clone, Copy)]
+pub struct Message<'a> {
+    /// Name of the context type.
+    pub context: &'a str,
+    /// Fields of the context type which can be interpolated into the templates.
+    pub fields: &'a [&'a str],
+    /// Message id in the catalogue files.
+    pub id: &'a str,
+    /// Languages the context type claims to support.
+    pub languages: &'a [Language],
+}
+
+impl Catalogue {
+    /// Load all the `<code>.ftl` and `<code>.po` files in the directory.
+    ///
+    /// Other files are ignored, an unknown [Language::code] or a
+    /// [pseudo-locale](Language::PSEUDO) is an error.
+    pub fn load(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut catalogue = Catalogue::default();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let (Some(code), Some(extension)) = (path.file_stem(), path.extension()) else {
+                continue;
+            };
+            let parse = match extension.to_str() {
+                Some("ftl") => parse_fluent,
+                Some("po") => parse_po,
+                _ => continue,
+            };
+            let language = code
+                .to_str()
+                .and_then(Language::from_code)
+                .ok_or_else(|| invalid_data(format!("unknown language {:?}", path)))?;
+            if language.is_pseudo() {
+                return Err(invalid_data(format!(
+                    "pseudo-locale {:?}, it is generated from English",
+                    path
+                )));
+            }
+            let source = fs::read_to_string(&path)?;
+            let templates = parse(&source).map_err(invalid_data)?;
+            catalogue
+                .templates
+                .entry(language)
+                .or_default()
+                .extend(templates);
+        }
+        Ok(catalogue)
+    }
+
+    /// Add a template for the message id.
+    pub fn insert(&mut self, language: Language, id: &str, template: &str) {
+        self.templates
+            .entry(language)
+            .or_default()
+            .insert(id.to_string(), template.to_string());
+    }
+
+    /// The template of the message id, in the `format!` syntax.
+    pub fn template(&self, language: Language, id: &str) -> Option<&str> {
+        self.templates.get(&language)?.get(id).map(String::as_str)
+    }
+
+    /// Generate the Rust source implementing [Localize](super::Localize) for the messages.
+    ///
+    /// A message missing in one of its claimed languages generates a `compile_error!`,
+    /// the [pseudo-locales](Language::PSEUDO) are skipped.
+    pub fn generate(&self, messages: &[Message]) -> String {
+        let mut code = String::new();
+        for message in messages {
+            let mut missing = Vec::new();
+            writeln!(code, "::bear_witness::translations! {{").unwrap();
+            writeln!(
+                code,
+                "    {} {{ {} }} {{",
+                message.context,
+                message.fields.join(", ")
+            )
+            .unwrap();
+            // the pseudo-locales are generated from English by translations!
+            for &language in message.languages.iter().filter(|l| !l.is_pseudo()) {
+                match self.template(language, message.id) {
+                    Some(template) => writeln!(
+                        code,
+                        "        ::bear_witness::i18n::{:?} => {:?},",
+                        language, template
+                    )
+                    .unwrap(),
+                    None => missing.push(language),
+                }
+            }
+            writeln!(code, "    }}\n}}").unwrap();
+            for language in missing {
+                writeln!(
+                    code,
+                    "compile_error!(\"message `{}` of `{}` is missing in `{}`\");",
+                    message.id,
+                    message.context,
+                    language.code()
+                )
+                .unwrap();
+            }
+        }
+        code
+    }
+}
+
+fn invalid_data(error: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Parse the subset of Fluent made of simple messages with variable placeables.
+///
+/// ```text
+/// # comment
+/// hello = Hello { $who }
+/// multiline = first line
+///     second line
+/// ```
+fn parse_fluent(source: &str) -> Result<HashMap<String, String>, String> {
+    let mut templates = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+    for line in source.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            let (_, template) = current
+                .as_mut()
+                .ok_or_else(|| format!("continuation without a message: {:?}", line))?;
+            if !template.is_empty() {
+                template.push('\n');
+            }
+            template.push_str(line.trim());
+            continue;
+        }
+        let (id, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("expected `id = value`: {:?}", line))?;
+        if let Some((id, template)) = current.take() {
+            templates.insert(id, fluent_template(&template)?);
+        }
+        current = Some((id.trim().to_string(), value.trim().to_string()));
+    }
+    if let Some((id, template)) = current {
+        templates.insert(id, fluent_template(&template)?);
+    }
+    Ok(templates)
+}
+
+/// Convert Fluent placeables to the `format!` syntax: `{ $who }` -> `{who}`.
+fn fluent_template(source: &str) -> Result<String, String> {
+    let mut template = String::new();
+    let mut rest = source;
+    while let Some(start) = rest.find(['{', '}']) {
+        push_escaped(&mut template, &rest[..start]);
+        if rest[start..].starts_with('}') {
+            return Err(format!("unbalanced `}}`: {:?}", source));
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unbalanced `{{`: {:?}", source))?;
+        let placeable = rest[start + 1..start + end].trim();
+        if let Some(variable) = placeable.strip_prefix('$') {
+            write!(template, "{{{}}}", variable).unwrap();
+        } else if let Some(literal) = placeable
+            .strip_prefix('"')
+            .and_then(|p| p.strip_suffix('"'))
+        {
+            push_escaped(&mut template, literal);
+        } else {
+            return Err(format!(
+                "unsupported placeable `{}`: {:?}",
+                placeable, source
+            ));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    push_escaped(&mut template, rest);
+    Ok(template)
+}
+
+fn push_escaped(template: &mut String, text: &str) {
+    template.push_str(&text.replace('{', "{{").replace('}', "}}"));
+}
+
+/// Parse `msgid` / `msgstr` pairs of a gettext file.
+///
+/// Placeholders are `{name}`, with `name` an identifier, every other brace is literal text.
+/// Entries with a `msgctxt` or plural forms are not supported and skipped,
+/// so are the entries flagged `fuzzy`, like `msgfmt` does.
+///
+/// ```text
+/// # comment
+/// #, fuzzy
+/// msgid "hello"
+/// msgstr "Hello {who}"
+/// ```
+fn parse_po(source: &str) -> Result<HashMap<String, String>, String> {
+    #[derive(Default)]
+    struct Entry {
+        skipped: bool,
+        id: Option<String>,
+        template: Option<String>,
+    }
+    /// The string extended by continuation lines.
+    enum Field {
+        None,
+        Id,
+        Template,
+        Skipped,
+    }
+
+    let mut templates = HashMap::new();
+    let mut finish = |entry: Entry| {
+        if let (false, Some(id), Some(template)) = (entry.skipped, entry.id, entry.template) {
+            // untranslated entries are empty, so is the header
+            if !id.is_empty() && !template.is_empty() {
+                templates.insert(id, po_template(&template));
+            }
+        }
+    };
+    let mut entry = Entry::default();
+    let mut field = Field::None;
+    for line in source.lines().map(str::trim) {
+        if let Some(flags) = line.strip_prefix("#,") {
+            // the flags come first, they start a new entry
+            if flags.split(',').any(|flag| flag.trim() == "fuzzy") {
+                finish(std::mem::take(&mut entry));
+                entry.skipped = true;
+                field = Field::Skipped;
+            }
+            continue;
+        }
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("msgctxt ") {
+            finish(std::mem::take(&mut entry));
+            po_string(rest)?;
+            entry.skipped = true;
+            field = Field::Skipped;
+        } else if let Some(rest) = line.strip_prefix("msgid ") {
+            if entry.id.is_some() {
+                finish(std::mem::take(&mut entry));
+            }
+            entry.id = Some(po_string(rest)?);
+            field = Field::Id;
+        } else if let Some(rest) = line.strip_prefix("msgid_plural ") {
+            po_string(rest)?;
+            entry.skipped = true;
+            field = Field::Skipped;
+        } else if let Some(rest) = line.strip_prefix("msgstr") {
+            if entry.id.is_none() {
+                return Err(format!("msgstr without msgid: {:?}", line));
+            }
+            if let Some(rest) = rest.strip_prefix(' ') {
+                entry.template = Some(po_string(rest)?);
+                field = Field::Template;
+            } else if rest.starts_with('[') {
+                // msgstr[n] of a plural entry
+                let (_, rest) = rest
+                    .split_once("] ")
+                    .ok_or_else(|| format!("unsupported line: {:?}", line))?;
+                po_string(rest)?;
+                entry.skipped = true;
+                field = Field::Skipped;
+            } else {
+                return Err(format!("unsupported line: {:?}", line));
+            }
+        } else if line.starts_with('"') {
+            // continuation of the last string
+            let string = po_string(line)?;
+            let target = match field {
+                Field::Id => entry.id.as_mut(),
+                Field::Template => entry.template.as_mut(),
+                Field::Skipped => continue,
+                Field::None => None,
+            };
+            target
+                .ok_or_else(|| format!("continuation without msgid: {:?}", line))?
+                .push_str(&string);
+        } else {
+            return Err(format!("unsupported line: {:?}", line));
+        }
+    }
+    finish(entry);
+    Ok(templates)
+}
+
+/// Turn a gettext string into a `format!` template, keeping the `{name}` placeholders
+/// and escaping every other brace.
+fn po_template(text: &str) -> String {
+    let mut template = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['{', '}']) {
+        push_escaped(&mut template, &rest[..start]);
+        rest = &rest[start..];
+        let placeholder = rest
+            .strip_prefix('{')
+            .and_then(|r| r.split_once('}'))
+            .map(|(name, _)| name)
+            .filter(|name| is_identifier(name));
+        match placeholder {
+            Some(name) => {
+                write!(template, "{{{}}}", name).unwrap();
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                push_escaped(&mut template, &rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    push_escaped(&mut template, rest);
+    template
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Unquote and unescape a gettext string.
+fn po_string(quoted: &str) -> Result<String, String> {
+    let inner = quoted
+        .strip_prefix('"')
+        .and_then(|q| q.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted string: {:?}", quoted))?;
+    let mut string = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            string.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => string.push('\n'),
+            Some('t') => string.push('\t'),
+            Some(c @ ('"' | '\\')) => string.push(c),
+            other => return Err(format!("unsupported escape {:?}: {:?}", other, quoted)),
+        }
+    }
+    Ok(string)
+}
@@ -0,0 +1,151 @@
+//! Translation coverage report.
+//!
+//! Register the message types with a [Registry], [Registry::coverage] returns the matrix of
+//! type × [Language], from the [Translations] of each type.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::coverage::*;
+//! #
+//! struct Farewell {
+//!     who: String,
+//! }
+//! bear_witness::translations! {
+//!     Farewell { who } {
+//!         English => "Goodbye {who}",
+//!         German => "Auf Wiedersehen {who}",
+//!     }
+//! }
+//!
+//! let coverage = Registry::default()
+//!     .register::<Context>()
+//!     .register::<Farewell>()
+//!     .coverage();
+//!
+//! assert!(coverage.covers::<Context>(Language::French));
+//! assert!(!coverage.covers::<Context>(Language::German));
+//! assert_eq!(
+//!     coverage.languages_of::<Farewell>(),
+//!     Some(vec![Language::English, Language::German, Language::Pseudo]),
+//! );
+//! assert_eq!(
+//!     coverage.missing(Language::German),
+//!     vec![std::any::type_name::<Context>()],
+//! );
+//! // languages translated by every registered type, the pseudo-locale follows English
+//! assert_eq!(coverage.complete(), vec![Language::English, Language::Pseudo]);
+//!
+//! let report = coverage.to_string();
+//! let mut lines = report.lines();
+//! assert!(lines.next().unwrap().starts_with("| type | en | fr | de |"));
+//! assert!(report.lines().next().unwrap().ends_with("| en-XA |"));
+//! assert!(lines.nth(1).unwrap().starts_with("| bear_witness::i18n::Context | ✓ | ✓ | ✗ |"));
+//! ```
+
+use std::any::type_name;
+use std::fmt;
+
+use super::{Language, Translations};
+
+/// The registered message types.
+#[derive(Debug, Default)]
+pub struct Registry {
+    types: Vec<(&'static str, Vec<Language>)>,
+}
+impl Registry {
+    /// Register the type `T`, by its [Translations].
+    pub fn register<T: Translations>(mut self) -> Self {
+        self.types.push((type_name::<T>(), T::languages()));
+        self
+    }
+
+    /// The coverage matrix of the registered types over all the [Language]s.
+    pub fn coverage(&self) -> Coverage {
+        let rows = self
+            .types
+            .iter()
+            .map(|(name, languages)| {
+                let row = columns().map(|l| languages.contains(l)).collect();
+                (*name, row)
+            })
+            .collect();
+        Coverage { rows }
+    }
+}
+
+/// The columns of the [Coverage]: [Language::ALL], then [Language::PSEUDO].
+fn columns() -> impl Iterator<Item = &'static Language> {
+    Language::ALL.iter().chain(Language::PSEUDO)
+}
+
+/// Matrix of type × [Language], whether the type is translated into the language.
+///
+/// The columns are [Language::ALL] then [Language::PSEUDO],
+/// [Display](fmt::Display) renders a markdown table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    rows: Vec<(&'static str, Vec<bool>)>,
+}
+impl Coverage {
+    /// The rows of the matrix: the type name, and a flag per column.
+    pub fn rows(&self) -> &[(&'static str, Vec<bool>)] {
+        &self.rows
+    }
+
+    /// The languages of the registered type `T`, [None] if `T` is not registered.
+    pub fn languages_of<T>(&self) -> Option<Vec<Language>> {
+        let (_, row) = self
+            .rows
+            .iter()
+            .find(|(name, _)| *name == type_name::<T>())?;
+        Some(
+            columns()
+                .zip(row)
+                .filter_map(|(language, covered)| covered.then_some(*language))
+                .collect(),
+        )
+    }
+
+    /// Is the registered type `T` translated into the language?
+    pub fn covers<T>(&self, language: Language) -> bool {
+        self.languages_of::<T>()
+            .is_some_and(|languages| languages.contains(&language))
+    }
+
+    /// The registered types not translated into the language.
+    pub fn missing(&self, language: Language) -> Vec<&'static str> {
+        let column = columns().position(|l| *l == language);
+        self.rows
+            .iter()
+            .filter(|(_, row)| column.is_some_and(|column| !row[column]))
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// The languages every registered type is translated into.
+    pub fn complete(&self) -> Vec<Language> {
+        columns()
+            .enumerate()
+            .filter(|(column, _)| self.rows.iter().all(|(_, row)| row[*column]))
+            .map(|(_, language)| *language)
+            .collect()
+    }
+}
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "| type |")?;
+        for language in columns() {
+            write!(f, " {} |", language.code())?;
+        }
+        write!(f, "\n|---|")?;
+        for _ in columns() {
+            write!(f, "---|")?;
+        }
+        for (name, row) in &self.rows {
+            write!(f, "\n| {} |", name)?;
+            for covered in row {
+                write!(f, " {} |", if *covered { "✓" } else { "✗" })?;
+            }
+        }
+        Ok(())
+    }
+}
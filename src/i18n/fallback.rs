@@ -0,0 +1,175 @@
+//! Language fallback chains, resolved at compile time.
+//!
+//! [Context](super::Context) has no German translation, so `context.localize(German)` does not compile.
+//! Instead of a runtime fallback, declare the chain of acceptable languages,
+//! the first one in the [Translations] of the type is picked by the compiler.
+//!
+//! The result is a [Fallback] naming both the requested and the resolved language,
+//! so a fallback is explicit in the types.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::fallback::*;
+//! #
+//! type GermanOrEnglish = (German, (English, ()));
+//!
+//! let context = Context { who: "World".to_string() };
+//! let localized: Fallback<Context, German, English> = context.localize_fallback(GermanOrEnglish::default());
+//! assert!(localized.fell_back());
+//! assert_eq!(render(localized), "Hello World");
+//!
+//! // the first language of the chain is used when translated
+//! let context = Context { who: "World".to_string() };
+//! let localized = context.localize_fallback((French, (English, ())));
+//! assert!(!localized.fell_back());
+//! assert_eq!(render(localized), "Bonjour World");
+//! ```
+//!
+//! A chain without any translated language is a type error.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::fallback::*;
+//! #
+//! let context = Context { who: "World".to_string() };
+//! render(context.localize_fallback((German, ())));
+//! // error: the trait bound `Unresolved: TypedLang` is not satisfied
+//! ```
+//!
+//! Languages are compared by their type-level [TypedLang::Id], a [Succ] / [Zero] number.
+
+use std::marker::PhantomData;
+
+use super::{Language, Localize, Localized, Render, Translations, TypedLang};
+
+/// Type-level zero.
+pub struct Zero;
+/// Type-level successor of `N`.
+pub struct Succ<N>(PhantomData<N>);
+
+/// Type-level boolean.
+pub trait Bool {
+    /// Type-level `or`.
+    type Or<B: Bool>: Bool;
+    /// `A` if true, `B` otherwise.
+    type If<A, B>;
+}
+/// Type-level `true`.
+pub struct True;
+impl Bool for True {
+    type Or<B: Bool> = True;
+    type If<A, B> = A;
+}
+/// Type-level `false`.
+pub struct False;
+impl Bool for False {
+    type Or<B: Bool> = B;
+    type If<A, B> = B;
+}
+
+/// Type-level equality of [Succ] / [Zero] numbers.
+pub trait Same<N> {
+    /// [True] if equal.
+    type Output: Bool;
+}
+impl Same<Zero> for Zero {
+    type Output = True;
+}
+impl<N> Same<Succ<N>> for Zero {
+    type Output = False;
+}
+impl<N> Same<Zero> for Succ<N> {
+    type Output = False;
+}
+impl<N: Same<M>, M> Same<Succ<M>> for Succ<N> {
+    type Output = N::Output;
+}
+
+/// Type-level membership of the language `L` in a list of [TypedLang]s.
+pub trait Contains<L> {
+    /// [True] if `L` is in the list.
+    type Output: Bool;
+}
+impl<L> Contains<L> for () {
+    type Output = False;
+}
+impl<L, H, Rest> Contains<L> for (H, Rest)
+where
+    L: TypedLang,
+    H: TypedLang,
+    H::Id: Same<L::Id>,
+    Rest: Contains<L>,
+{
+    type Output = <<H::Id as Same<L::Id>>::Output as Bool>::Or<Rest::Output>;
+}
+
+/// No language of the chain is translated.
+pub struct Unresolved;
+
+/// Resolve a fallback chain, e.g. `(German, (English, ()))`, against the [Translations] of `T`.
+pub trait Resolve<T: Translations + ?Sized> {
+    /// The first language of the chain `T` is translated into.
+    type Resolved;
+}
+impl<T: Translations + ?Sized> Resolve<T> for () {
+    type Resolved = Unresolved;
+}
+impl<T, L, Rest> Resolve<T> for (L, Rest)
+where
+    T: Translations + ?Sized,
+    T::Languages: Contains<L>,
+    Rest: Resolve<T>,
+{
+    type Resolved = <<T::Languages as Contains<L>>::Output as Bool>::If<L, Rest::Resolved>;
+}
+
+/// Value localized into `Resolved`, the first translated language of a chain
+/// starting at `Requested`.
+pub struct Fallback<T, Requested, Resolved: TypedLang> {
+    localized: Localized<T, Resolved>,
+    requested: PhantomData<Requested>,
+}
+impl<T, Requested: TypedLang, Resolved: TypedLang> Fallback<T, Requested, Resolved> {
+    /// The requested [Language].
+    pub fn requested(&self) -> Language {
+        Requested::LANGUAGE
+    }
+
+    /// The [Language] used.
+    pub fn resolved(&self) -> Language {
+        Resolved::LANGUAGE
+    }
+
+    /// Was a language other than the requested one used?
+    pub fn fell_back(&self) -> bool {
+        Requested::LANGUAGE != Resolved::LANGUAGE
+    }
+
+    /// The [Localized] value in the language used.
+    pub fn into_localized(self) -> Localized<T, Resolved> {
+        self.localized
+    }
+}
+impl<T: Localize<R>, Q, R: TypedLang> Render<R> for Fallback<T, Q, R> {
+    fn render(&self) -> String {
+        self.localized.render()
+    }
+}
+
+/// Localize into the first translated language of a fallback chain.
+pub trait LocalizeFallback: Translations + Sized {
+    /// Resolve the `chain` and localize the value, see the [module](self) docs.
+    fn localize_fallback<L, Rest>(
+        self,
+        _chain: (L, Rest),
+    ) -> Fallback<Self, L, <(L, Rest) as Resolve<Self>>::Resolved>
+    where
+        (L, Rest): Resolve<Self>,
+        <(L, Rest) as Resolve<Self>>::Resolved: TypedLang + Default,
+        Self: Localize<<(L, Rest) as Resolve<Self>>::Resolved>,
+    {
+        Fallback {
+            localized: self.localize(Default::default()),
+            requested: PhantomData,
+        }
+    }
+}
+impl<T: Translations> LocalizeFallback for T {}
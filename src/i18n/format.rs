@@ -0,0 +1,307 @@
+//! Locale-aware number, date and currency formatting.
+//!
+//! Each typed language has a formatting [Profile], use it inside [Localize](super::Localize) impls
+//! instead of formatting numbers and dates by hand.
+//!
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::format::*;
+//! #
+//! struct Invoice {
+//!     total: i64,
+//!     due: Date,
+//! }
+//! impl Localize<English> for Invoice {
+//!     fn translate(&self, lang: &English) -> String {
+//!         let profile = lang.profile();
+//!         format!("{} due {}", profile.currency(self.total, "$"), profile.date(self.due))
+//!     }
+//! }
+//! impl Localize<German> for Invoice {
+//!     fn translate(&self, lang: &German) -> String {
+//!         let profile = lang.profile();
+//!         format!("{} fällig am {}", profile.currency(self.total, "€"), profile.date(self.due))
+//!     }
+//! }
+//!
+//! let invoice = || Invoice { total: 123456, due: Date { year: 2024, month: 12, day: 31 } };
+//! assert_eq!(render(invoice().localize(English)), "$1,234.56 due 12/31/2024");
+//! assert_eq!(render(invoice().localize(German)), "1.234,56\u{a0}€ fällig am 31.12.2024");
+//! ```
+//!
+//! English
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::format::*;
+//! #
+//! let profile = English.profile();
+//! assert_eq!(profile.integer(-1234567), "-1,234,567");
+//! assert_eq!(profile.decimal(1234.5, 2), "1,234.50");
+//! assert_eq!(profile.decimal(0.125, 0), "0");
+//! assert_eq!(profile.date(Date { year: 2024, month: 3, day: 7 }), "03/07/2024");
+//! assert_eq!(profile.currency(-5, "$"), "-$0.05");
+//! ```
+//!
+//! French
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::format::*;
+//! #
+//! let profile = French.profile();
+//! assert_eq!(profile.integer(-1234567), "-1\u{202f}234\u{202f}567");
+//! assert_eq!(profile.decimal(1234.5, 2), "1\u{202f}234,50");
+//! assert_eq!(profile.date(Date { year: 2024, month: 3, day: 7 }), "07/03/2024");
+//! assert_eq!(profile.currency(123456, "€"), "1\u{202f}234,56\u{a0}€");
+//! ```
+//!
+//! German
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::format::*;
+//! #
+//! let profile = German.profile();
+//! assert_eq!(profile.integer(999), "999");
+//! assert_eq!(profile.integer(1000), "1.000");
+//! assert_eq!(profile.decimal(-0.5, 1), "-0,5");
+//! assert_eq!(profile.date(Date { year: 2024, month: 3, day: 7 }), "07.03.2024");
+//! assert_eq!(profile.currency(-123456, "€"), "-1.234,56\u{a0}€");
+//! ```
+//!
+//! Arabic and Hebrew
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::format::*;
+//! #
+//! let date = Date { year: 2024, month: 3, day: 7 };
+//! assert_eq!(Arabic.profile().date(date), "07/03/2024");
+//! assert_eq!(Hebrew.profile().date(date), "07.03.2024");
+//! assert_eq!(Hebrew.profile().currency(123456, "₪"), "1,234.56\u{a0}₪");
+//! ```
+//!
+//! Regional variants
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::format::*;
+//! #
+//! let date = Date { year: 2024, month: 3, day: 7 };
+//! assert_eq!(EnglishUS.profile(), English.profile());
+//! assert_eq!(EnglishGB.profile().date(date), "07/03/2024");
+//! assert_eq!(EnglishGB.profile().currency(123456, "£"), "£1,234.56");
+//! assert_eq!(FrenchCA.profile().date(date), "2024-03-07");
+//! assert_eq!(FrenchCA.profile().integer(1234), "1\u{a0}234");
+//! assert_eq!(GermanCH.profile().decimal(1234.5, 2), "1’234.50");
+//! assert_eq!(GermanCH.profile().currency(123456, "CHF"), "CHF\u{a0}1’234.56");
+//! ```
+
+use super::{
+    Arabic, English, EnglishGB, EnglishUS, French, FrenchCA, German, GermanCH, Hebrew, TypedLang,
+};
+
+/// Order of the day, month and year in a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// 31/12/2024
+    DayMonthYear,
+    /// 12/31/2024
+    MonthDayYear,
+    /// 2024-12-31
+    YearMonthDay,
+}
+
+/// Placement of the currency symbol around the amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurrencyPlacement {
+    /// $1.00
+    Before,
+    /// CHF 1.00, separated by a no-break space
+    BeforeSpaced,
+    /// 1,00 €, separated by a no-break space
+    After,
+}
+
+/// A calendar date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    /// Year.
+    pub year: i32,
+    /// Month, 1 to 12.
+    pub month: u8,
+    /// Day of the month, 1 to 31.
+    pub day: u8,
+}
+
+/// Formatting conventions of a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    /// Separates the integer and fractional parts.
+    pub decimal_separator: char,
+    /// Separates groups of three digits in the integer part.
+    pub grouping_separator: char,
+    /// Order of the date components.
+    pub date_order: DateOrder,
+    /// Separates the date components.
+    pub date_separator: char,
+    /// Placement of the currency symbol.
+    pub currency_placement: CurrencyPlacement,
+}
+impl Profile {
+    /// Format an integer with grouped digits.
+    pub fn integer(&self, n: i64) -> String {
+        let sign = if n < 0 { "-" } else { "" };
+        format!("{}{}", sign, self.group(&n.unsigned_abs().to_string()))
+    }
+
+    /// Format a number rounded to `decimals` fractional digits.
+    pub fn decimal(&self, x: f64, decimals: usize) -> String {
+        let digits = format!("{:.*}", decimals, x.abs());
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        // no negative zero
+        let sign = if x < 0.0 && digits.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+            "-"
+        } else {
+            ""
+        };
+        let mut formatted = format!("{}{}", sign, self.group(integer));
+        if !fraction.is_empty() {
+            formatted.push(self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    /// Format a date, with two digits days and months.
+    pub fn date(&self, date: Date) -> String {
+        let day = format!("{:02}", date.day);
+        let month = format!("{:02}", date.month);
+        let year = date.year.to_string();
+        let parts = match self.date_order {
+            DateOrder::DayMonthYear => [day, month, year],
+            DateOrder::MonthDayYear => [month, day, year],
+            DateOrder::YearMonthDay => [year, month, day],
+        };
+        parts.join(&self.date_separator.to_string())
+    }
+
+    /// Format an amount given in minor units (cents), with the currency symbol.
+    pub fn currency(&self, minor_units: i64, symbol: &str) -> String {
+        let sign = if minor_units < 0 { "-" } else { "" };
+        let amount = minor_units.unsigned_abs();
+        let amount = format!(
+            "{}{}{:02}",
+            self.group(&(amount / 100).to_string()),
+            self.decimal_separator,
+            amount % 100
+        );
+        match self.currency_placement {
+            CurrencyPlacement::Before => format!("{}{}{}", sign, symbol, amount),
+            CurrencyPlacement::BeforeSpaced => format!("{}{}\u{a0}{}", sign, symbol, amount),
+            CurrencyPlacement::After => format!("{}{}\u{a0}{}", sign, amount, symbol),
+        }
+    }
+
+    /// Insert the grouping separator every three digits, from the right.
+    fn group(&self, digits: &str) -> String {
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(self.grouping_separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+/// Formatting [Profile] of a typed language.
+pub trait Format: TypedLang {
+    /// The formatting conventions.
+    const PROFILE: Profile;
+
+    /// The formatting conventions, for use inside [Localize](super::Localize) impls.
+    fn profile(&self) -> Profile {
+        Self::PROFILE
+    }
+}
+
+impl Format for English {
+    const PROFILE: Profile = Profile {
+        decimal_separator: '.',
+        grouping_separator: ',',
+        date_order: DateOrder::MonthDayYear,
+        date_separator: '/',
+        currency_placement: CurrencyPlacement::Before,
+    };
+}
+
+/// Digits are grouped with a narrow no-break space.
+impl Format for French {
+    const PROFILE: Profile = Profile {
+        decimal_separator: ',',
+        grouping_separator: '\u{202f}',
+        date_order: DateOrder::DayMonthYear,
+        date_separator: '/',
+        currency_placement: CurrencyPlacement::After,
+    };
+}
+
+impl Format for German {
+    const PROFILE: Profile = Profile {
+        decimal_separator: ',',
+        grouping_separator: '.',
+        date_order: DateOrder::DayMonthYear,
+        date_separator: '.',
+        currency_placement: CurrencyPlacement::After,
+    };
+}
+
+/// Latin digits.
+impl Format for Arabic {
+    const PROFILE: Profile = Profile {
+        decimal_separator: '.',
+        grouping_separator: ',',
+        date_order: DateOrder::DayMonthYear,
+        date_separator: '/',
+        currency_placement: CurrencyPlacement::After,
+    };
+}
+
+impl Format for Hebrew {
+    const PROFILE: Profile = Profile {
+        decimal_separator: '.',
+        grouping_separator: ',',
+        date_order: DateOrder::DayMonthYear,
+        date_separator: '.',
+        currency_placement: CurrencyPlacement::After,
+    };
+}
+
+impl Format for EnglishUS {
+    const PROFILE: Profile = English::PROFILE;
+}
+
+impl Format for EnglishGB {
+    const PROFILE: Profile = Profile {
+        date_order: DateOrder::DayMonthYear,
+        ..English::PROFILE
+    };
+}
+
+/// Digits are grouped with a no-break space, dates are ISO 8601.
+impl Format for FrenchCA {
+    const PROFILE: Profile = Profile {
+        grouping_separator: '\u{a0}',
+        date_order: DateOrder::YearMonthDay,
+        date_separator: '-',
+        ..French::PROFILE
+    };
+}
+
+/// Digits are grouped with an apostrophe.
+impl Format for GermanCH {
+    const PROFILE: Profile = Profile {
+        decimal_separator: '.',
+        grouping_separator: '’',
+        currency_placement: CurrencyPlacement::BeforeSpaced,
+        ..German::PROFILE
+    };
+}
@@ -0,0 +1,145 @@
+//! Typed messages.
+//!
+//! The [message!](crate::message!) macro defines a message type from its key, its named
+//! arguments with their types, and a template for each language.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::message::*;
+//! #
+//! bear_witness::message! {
+//!     /// Unread messages notification.
+//!     pub Unread = "unread" {
+//!         pub who: String,
+//!         pub count: u64,
+//!     } {
+//!         English => "{who}, you have {count} unread messages",
+//!         French => "{who}, vous avez {count} messages non lus",
+//!     }
+//! }
+//!
+//! let unread = Unread { who: "World".to_string(), count: 3 };
+//! assert_eq!(render(unread.localize(French)), "World, vous avez 3 messages non lus");
+//! assert_eq!(Unread::KEY, "unread");
+//! assert_eq!(Unread::ARGUMENTS, &["who", "count"]);
+//! assert_eq!(Unread::languages(), vec![Language::English, Language::French, Language::Pseudo]);
+//! ```
+//!
+//! Every template is checked at compile time to use exactly the declared arguments.
+//! A translator dropping `{who}` fails the compilation,
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! bear_witness::message! {
+//!     pub Unread = "unread" {
+//!         pub who: String,
+//!         pub count: u64,
+//!     } {
+//!         English => "{who}, you have {count} unread messages",
+//!         French => "Vous avez {count} messages non lus",
+//!         // error: named argument never used
+//!     }
+//! }
+//! ```
+//!
+//! and so does an unknown placeholder.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! bear_witness::message! {
+//!     pub Unread = "unread" {
+//!         pub who: String,
+//!         pub count: u64,
+//!     } {
+//!         English => "{who}, you have {count} unread {kind}",
+//!         // error: cannot find value `kind` in this scope
+//!     }
+//! }
+//! ```
+//!
+//! The argument types are checked against the placeholders as well.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! struct Opaque;
+//! bear_witness::message! {
+//!     pub Unread = "unread" {
+//!         pub who: Opaque,
+//!     } {
+//!         English => "{who}, you have unread messages",
+//!         // error: `Opaque` doesn't implement `std::fmt::Display`
+//!     }
+//! }
+//! ```
+//!
+//! The forms of a `plural` or `gender` table may leave out arguments, e.g. the count
+//! in the `one` form, so they are not accepted: define the context type with
+//! [translations!](crate::translations!) instead.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! bear_witness::message! {
+//!     pub Unread = "unread" {
+//!         pub who: String,
+//!         pub count: u64,
+//!     } {
+//!         English => plural(count) {
+//!             one: "{who}, you have {count} unread message",
+//!             other: "You have many unread messages",
+//!         },
+//!         // error: no rules expected `plural`
+//!     }
+//! }
+//! ```
+
+/// Signature of a message type, generated by [message!](crate::message!).
+pub trait TypedMessage {
+    /// Key of the message, e.g. its id in the [catalogue](super::catalogue) files.
+    const KEY: &'static str;
+    /// Names of the arguments, every template uses exactly these.
+    const ARGUMENTS: &'static [&'static str];
+}
+
+/// Define a message type with typed arguments, and its templates.
+///
+/// ```text
+/// message! {
+///     /// Documentation of the message.
+///     pub <Message type> = "<key>" {
+///         pub <argument>: <type>,
+///         ...
+///     } {
+///         <TypedLang> => "<template>",
+///         ...
+///     }
+/// }
+/// ```
+///
+/// The templates are checked by [translations!](crate::translations!),
+/// see the [message](crate::i18n::message) module for an example.
+/// Only plain templates are accepted, not the `plural` and `gender` tables.
+#[macro_export]
+macro_rules! message {
+    ($(
+        $(#[$meta:meta])*
+        $vis:vis $name:ident = $key:literal {
+            $($field_vis:vis $field:ident: $ty:ty),* $(,)?
+        } {
+            $($lang:ty => $template:literal),* $(,)?
+        }
+    )*) => {$(
+        $(#[$meta])*
+        $vis struct $name {
+            $(
+                #[doc = concat!("Argument `", stringify!($field), "`.")]
+                $field_vis $field: $ty,
+            )*
+        }
+        impl $crate::i18n::message::TypedMessage for $name {
+            const KEY: &'static str = $key;
+            const ARGUMENTS: &'static [&'static str] = &[$(stringify!($field)),*];
+        }
+        $crate::translations! {
+            $name { $($field),* } { $($lang => $template),* }
+        }
+    )*};
+}
@@ -0,0 +1,242 @@
+//! `Accept-Language` negotiation.
+//!
+//! [parse] reads the weighted language ranges of the header, most preferred first.
+//! [lookup] and [filter] match them against the supported languages, following RFC 4647.
+//! [negotiate] picks the best [Language] among the [Translations] of a type.
+//!
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::negotiate::*;
+//! #
+//! let header = "de-CH, fr-CA;q=0.8, en;q=0.5";
+//! let language = negotiate::<Context>(header);
+//! // no German, fr-CA is truncated to fr
+//! assert_eq!(language, Some(Language::French));
+//!
+//! let context = Context { who: "World".to_string() };
+//! assert_eq!(dispatch(&context, language.unwrap()), Ok("Bonjour World".to_string()));
+//! assert_eq!(negotiate::<Context>("de, *;q=0.1"), None);
+//! ```
+//!
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::negotiate::*;
+//! #
+//! let ranges = parse("fr-CA;q=0.8, EN ;Q=1, *;q=0.1, de;q=0");
+//! assert_eq!(
+//!     ranges,
+//!     vec![
+//!         LanguageRange { range: "en".to_string(), quality: 1000 },
+//!         LanguageRange { range: "fr-ca".to_string(), quality: 800 },
+//!         LanguageRange { range: "*".to_string(), quality: 100 },
+//!         LanguageRange { range: "de".to_string(), quality: 0 },
+//!     ],
+//! );
+//!
+//! // lookup finds the single best match, truncating the ranges
+//! assert_eq!(lookup(&parse("en-AU, fr"), Language::ALL), Some(Language::English));
+//! assert_eq!(lookup(&parse("en-GB-oxendict"), Language::ALL), Some(Language::EnglishGB));
+//!
+//! // filtering returns all the matches by preference, the wildcard matches the rest,
+//! // a zero quality excludes the language
+//! assert_eq!(
+//!     filter(&parse("fr, *;q=0.5, de;q=0, en-US;q=0"), Language::ALL),
+//!     vec![
+//!         Language::French,
+//!         Language::FrenchCA,
+//!         Language::English,
+//!         Language::Arabic,
+//!         Language::Hebrew,
+//!         Language::EnglishGB,
+//!     ],
+//! );
+//! ```
+//!
+//! Malformed entries of the header are skipped, never rejected as a whole.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::negotiate::*;
+//! #
+//! assert_eq!(parse(""), vec![]);
+//! assert_eq!(parse(",,;q=1, en;q=2, fr;q=0.1234, de;q=x, en_GB, -en, en-, 123"), vec![]);
+//! assert_eq!(parse("en;level=1, fr;q=1.000"), vec![LanguageRange { range: "fr".to_string(), quality: 1000 }]);
+//! ```
+//!
+//! Property test over generated malformed headers: parsing never fails,
+//! and the negotiated languages are always supported.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::negotiate::*;
+//! #
+//! // xorshift, so the test is reproducible
+//! let mut state = 0x2545_f491_4f6c_dd1d_u64;
+//! let mut random = move |n: usize| {
+//!     state ^= state << 13;
+//!     state ^= state >> 7;
+//!     state ^= state << 17;
+//!     state as usize % n
+//! };
+//! let pieces = [
+//!     "en", "fr", "de", "CH", "ca", "-", "_", ",", ";", "q", "=", "0", "1", ".", "5", "*", " ",
+//!     "\t", "é", "x", "-gb", ";q=", "0.", "1.0001", "\u{0}", "🐻",
+//! ];
+//! for _ in 0..10_000 {
+//!     let header: String = (0..random(16)).map(|_| pieces[random(pieces.len())]).collect();
+//!
+//!     let ranges = parse(&header);
+//!     assert!(ranges.iter().all(|range| range.quality <= 1000));
+//!     assert!(ranges.windows(2).all(|pair| pair[0].quality >= pair[1].quality));
+//!
+//!     let supported = Context::languages();
+//!     if let Some(language) = negotiate::<Context>(&header) {
+//!         assert!(supported.contains(&language), "{:?} -> {:?}", header, language);
+//!     }
+//!     let filtered = filter(&ranges, &supported);
+//!     assert!(filtered.iter().all(|language| supported.contains(language)));
+//!     assert!(filtered.iter().enumerate().all(|(i, language)| !filtered[..i].contains(language)));
+//! }
+//! ```
+
+use super::{Language, Translations};
+
+/// A language range of the header, with its quality.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageRange {
+    /// Lowercase language range, e.g. `en-gb`, or the wildcard `*`.
+    pub range: String,
+    /// Quality in thousandths, `q=0.8` is 800.
+    pub quality: u16,
+}
+
+/// Parse the language ranges of an `Accept-Language` header, by decreasing quality.
+///
+/// Ranges of equal quality keep their order, malformed entries are skipped.
+pub fn parse(header: &str) -> Vec<LanguageRange> {
+    let mut ranges: Vec<LanguageRange> = header.split(',').filter_map(parse_entry).collect();
+    ranges.sort_by_key(|range| std::cmp::Reverse(range.quality));
+    ranges
+}
+
+fn parse_entry(entry: &str) -> Option<LanguageRange> {
+    let mut parts = entry.split(';');
+    let range = parts.next()?.trim();
+    if !is_range(range) {
+        return None;
+    }
+    let quality = match parts.next() {
+        None => 1000,
+        Some(weight) => {
+            let weight = weight.trim();
+            let value = weight
+                .strip_prefix("q=")
+                .or_else(|| weight.strip_prefix("Q="))?;
+            parse_quality(value)?
+        }
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(LanguageRange {
+        range: range.to_ascii_lowercase(),
+        quality,
+    })
+}
+
+/// `*`, or `1*8ALPHA *("-" 1*8alphanum)`.
+fn is_range(range: &str) -> bool {
+    if range == "*" {
+        return true;
+    }
+    let mut subtags = range.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    let valid = |subtag: &str, first: bool| {
+        (1..=8).contains(&subtag.len())
+            && subtag
+                .bytes()
+                .all(|b| b.is_ascii_alphabetic() || (!first && b.is_ascii_digit()))
+    };
+    valid(primary, true) && subtags.all(|subtag| valid(subtag, false))
+}
+
+/// `0[.ddd]` or `1[.000]`, in thousandths.
+fn parse_quality(value: &str) -> Option<u16> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let thousandths: u16 = format!("{:0<3}", fraction).parse().ok()?;
+    match integer {
+        "0" => Some(thousandths),
+        "1" if thousandths == 0 => Some(1000),
+        _ => None,
+    }
+}
+
+/// RFC 4647 lookup: the single best supported language.
+///
+/// Each range is tried by decreasing quality, truncated one subtag at a time until a
+/// supported [Language::code] matches. The wildcard and zero quality ranges are ignored,
+/// so are the [pseudo-locales](Language::PSEUDO).
+pub fn lookup(ranges: &[LanguageRange], supported: &[Language]) -> Option<Language> {
+    ranges
+        .iter()
+        .filter(|range| range.quality > 0 && range.range != "*")
+        .find_map(|range| {
+            let mut range = range.range.as_str();
+            loop {
+                let found = supported.iter().find(|language| {
+                    !language.is_pseudo() && language.code().eq_ignore_ascii_case(range)
+                });
+                if found.is_some() {
+                    return found.copied();
+                }
+                range = &range[..range.rfind('-')?];
+                // a single character subtag cannot end the range
+                if range.len() > 1 && range.as_bytes()[range.len() - 2] == b'-' {
+                    range = &range[..range.len() - 2];
+                }
+            }
+        })
+}
+
+/// RFC 4647 basic filtering: all the supported languages matching a range, by decreasing quality.
+///
+/// A range matches a language equal to it, or starting with it followed by `-`,
+/// the wildcard matches any language. Languages matching a zero quality range are excluded,
+/// so are the [pseudo-locales](Language::PSEUDO).
+pub fn filter(ranges: &[LanguageRange], supported: &[Language]) -> Vec<Language> {
+    let matches = |range: &LanguageRange, language: &Language| {
+        let code = language.code().to_ascii_lowercase();
+        range.range == "*"
+            || code == range.range
+            || code
+                .strip_prefix(range.range.as_str())
+                .is_some_and(|rest| rest.starts_with('-'))
+    };
+    let excluded: Vec<Language> = supported
+        .iter()
+        .filter(|language| {
+            ranges
+                .iter()
+                .any(|range| range.quality == 0 && range.range != "*" && matches(range, language))
+        })
+        .copied()
+        .collect();
+    let mut filtered = Vec::new();
+    for range in ranges.iter().filter(|range| range.quality > 0) {
+        for language in supported.iter().filter(|language| !language.is_pseudo()) {
+            if matches(range, language)
+                && !excluded.contains(language)
+                && !filtered.contains(language)
+            {
+                filtered.push(*language);
+            }
+        }
+    }
+    filtered
+}
+
+/// The best [Language] among the [Translations] of `T` for the `Accept-Language` header.
+pub fn negotiate<T: Translations>(header: &str) -> Option<Language> {
+    lookup(&parse(header), &T::languages())
+}
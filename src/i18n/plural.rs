@@ -0,0 +1,432 @@
+//! Plural and gender aware messages.
+//!
+//! Each language declares the CLDR plural categories it distinguishes with [Plural::Forms],
+//! and its grammatical genders with [Gendered::Forms].
+//! These are structs with a field per form, a [Localize](super::Localize) impl has to provide all of them.
+//!
+//! [translations!](crate::translations!) selects the form from a count or a [Gender] field
+//! of the context, e.g. [Inbox](super::Inbox) and [Welcome](super::Welcome).
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::plural::*;
+//! #
+//! let inbox = |unread| Inbox { who: "Camille".to_string(), unread };
+//! assert_eq!(render(inbox(1).localize(English)), "Camille, you have 1 unread message");
+//! assert_eq!(render(inbox(2).localize(English)), "Camille, you have 2 unread messages");
+//! assert_eq!(render(inbox(0).localize(French)), "Camille, vous avez 0 message non lu");
+//! assert_eq!(
+//!     render(inbox(1_000_000).localize(French)),
+//!     "Camille, vous avez 1000000 de messages non lus",
+//! );
+//!
+//! let welcome = Welcome { who: "Camille".to_string(), gender: Gender::Feminine };
+//! assert_eq!(render(welcome.localize(French)), "Bienvenue Camille");
+//! ```
+//!
+//! A form missing from the table fails the compilation of the generated impl.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! struct Guests {
+//!     guests: u64,
+//! }
+//! bear_witness::translations! {
+//!     Guests { guests } {
+//!         French => plural(guests) {
+//!             one: "{guests} invité",
+//!             other: "{guests} invités",
+//!         },
+//!         // error: missing field `many` in initializer of `OneManyOther<_>`
+//!     }
+//! }
+//! ```
+//!
+//! The forms can also be selected by hand.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::plural::*;
+//! #
+//! struct Invited {
+//!     who: String,
+//!     gender: Gender,
+//!     guests: u64,
+//! }
+//! impl Localize<English> for Invited {
+//!     fn translate(&self, lang: &English) -> String {
+//!         let guests = lang.plural(self.guests, OneOther { one: "guest", other: "guests" });
+//!         format!("{} is invited with {} {}", self.who, self.guests, guests)
+//!     }
+//! }
+//! impl Localize<French> for Invited {
+//!     fn translate(&self, lang: &French) -> String {
+//!         let invited = lang.gender(self.gender, MasculineFeminine { masculine: "invité", feminine: "invitée" });
+//!         let guests = lang.plural(
+//!             self.guests,
+//!             OneManyOther { one: "invité", many: "d’invités", other: "invités" },
+//!         );
+//!         format!("{} est {} avec {} {}", self.who, invited, self.guests, guests)
+//!     }
+//! }
+//!
+//! let invited = |guests| Invited { who: "Camille".to_string(), gender: Gender::Feminine, guests };
+//! assert_eq!(render(invited(1).localize(English)), "Camille is invited with 1 guest");
+//! assert_eq!(render(invited(2).localize(English)), "Camille is invited with 2 guests");
+//! assert_eq!(render(invited(0).localize(French)), "Camille est invitée avec 0 invité");
+//! assert_eq!(render(invited(2).localize(French)), "Camille est invitée avec 2 invités");
+//! assert_eq!(render(invited(1_000_000).localize(French)), "Camille est invitée avec 1000000 d’invités");
+//! ```
+//!
+//! The categories follow the CLDR rules for cardinal numbers.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::plural::*;
+//! #
+//! assert_eq!(English::category(0), PluralCategory::Other);
+//! assert_eq!(English::category(1), PluralCategory::One);
+//! assert_eq!(French::category(0), PluralCategory::One);
+//! assert_eq!(French::category(2), PluralCategory::Other);
+//! assert_eq!(French::category(2_000_000), PluralCategory::Many);
+//! assert_eq!(German::category(1), PluralCategory::One);
+//! assert_eq!(German::category(1_000_000), PluralCategory::Other);
+//! assert_eq!(Arabic::category(0), PluralCategory::Zero);
+//! assert_eq!(Arabic::category(2), PluralCategory::Two);
+//! assert_eq!(Arabic::category(103), PluralCategory::Few);
+//! assert_eq!(Arabic::category(11), PluralCategory::Many);
+//! assert_eq!(Arabic::category(100), PluralCategory::Other);
+//! assert_eq!(Hebrew::category(2), PluralCategory::Two);
+//! assert_eq!(Hebrew::category(20), PluralCategory::Other);
+//!
+//! let article = |gender| German.gender(gender, MasculineFeminineNeuter { masculine: "der", feminine: "die", neuter: "das" });
+//! assert_eq!(article(Gender::Neuter), "das");
+//! assert_eq!(French.gender(Gender::Neuter, MasculineFeminine { masculine: "le", feminine: "la" }), "le");
+//! ```
+//!
+//! Forgetting a form required by the language is a type error.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::plural::*;
+//! #
+//! struct Guests(u64);
+//! impl Localize<French> for Guests {
+//!     fn translate(&self, lang: &French) -> String {
+//!         lang.plural(self.0, OneManyOther { one: "invité", other: "invités" }).to_string()
+//!         // error: missing field `many` in initializer of `OneManyOther<_>`
+//!     }
+//! }
+//! ```
+//!
+//! So is using the forms of another language.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::plural::*;
+//! #
+//! struct Guests(u64);
+//! impl Localize<French> for Guests {
+//!     fn translate(&self, lang: &French) -> String {
+//!         lang.plural(self.0, OneOther { one: "invité", other: "invités" }).to_string()
+//!         // error: mismatched types, expected `OneManyOther<_>`, found `OneOther<_>`
+//!     }
+//! }
+//! ```
+
+use super::region::Regional;
+use super::{Arabic, English, French, German, Hebrew, TypedLang};
+
+/// CLDR plural category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    /// zero
+    Zero,
+    /// one
+    One,
+    /// two
+    Two,
+    /// few
+    Few,
+    /// many
+    Many,
+    /// other
+    Other,
+}
+
+/// Grammatical gender.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gender {
+    /// masculine
+    Masculine,
+    /// feminine
+    Feminine,
+    /// neuter
+    Neuter,
+}
+
+/// Plural forms of a message, one for each category the language distinguishes.
+pub trait PluralForms<T> {
+    /// The form for the category, categories the language does not use are never asked for.
+    fn select(self, category: PluralCategory) -> T;
+}
+
+/// Gender forms of a message, one for each gender the language distinguishes.
+pub trait GenderForms<T> {
+    /// The form for the gender, genders the language does not have map onto the closest one.
+    fn select(self, gender: Gender) -> T;
+}
+
+/// Plural rules of a typed language.
+pub trait Plural: TypedLang {
+    /// The plural forms required by the language.
+    type Forms<T>: PluralForms<T>;
+
+    /// CLDR plural category of the cardinal number `n`.
+    fn category(n: u64) -> PluralCategory;
+
+    /// Select the form for the cardinal number `n`.
+    fn plural<T>(&self, n: u64, forms: Self::Forms<T>) -> T {
+        forms.select(Self::category(n))
+    }
+}
+
+/// Grammatical genders of a typed language.
+pub trait Gendered: TypedLang {
+    /// The gender forms required by the language.
+    type Forms<T>: GenderForms<T>;
+
+    /// Select the form for the gender.
+    fn gender<T>(&self, gender: Gender, forms: Self::Forms<T>) -> T {
+        forms.select(gender)
+    }
+}
+
+/// Plural forms of languages distinguishing only one and other.
+#[derive(Debug, Clone, Copy)]
+pub struct OneOther<T> {
+    /// one
+    pub one: T,
+    /// other
+    pub other: T,
+}
+impl<T> PluralForms<T> for OneOther<T> {
+    fn select(self, category: PluralCategory) -> T {
+        match category {
+            PluralCategory::One => self.one,
+            _ => self.other,
+        }
+    }
+}
+
+/// Plural forms of languages distinguishing one, many and other.
+#[derive(Debug, Clone, Copy)]
+pub struct OneManyOther<T> {
+    /// one
+    pub one: T,
+    /// many
+    pub many: T,
+    /// other
+    pub other: T,
+}
+impl<T> PluralForms<T> for OneManyOther<T> {
+    fn select(self, category: PluralCategory) -> T {
+        match category {
+            PluralCategory::One => self.one,
+            PluralCategory::Many => self.many,
+            _ => self.other,
+        }
+    }
+}
+
+/// Plural forms of languages distinguishing one, two and other.
+#[derive(Debug, Clone, Copy)]
+pub struct OneTwoOther<T> {
+    /// one
+    pub one: T,
+    /// two
+    pub two: T,
+    /// other
+    pub other: T,
+}
+impl<T> PluralForms<T> for OneTwoOther<T> {
+    fn select(self, category: PluralCategory) -> T {
+        match category {
+            PluralCategory::One => self.one,
+            PluralCategory::Two => self.two,
+            _ => self.other,
+        }
+    }
+}
+
+/// Plural forms of languages distinguishing all the categories.
+#[derive(Debug, Clone, Copy)]
+pub struct ZeroOneTwoFewManyOther<T> {
+    /// zero
+    pub zero: T,
+    /// one
+    pub one: T,
+    /// two
+    pub two: T,
+    /// few
+    pub few: T,
+    /// many
+    pub many: T,
+    /// other
+    pub other: T,
+}
+impl<T> PluralForms<T> for ZeroOneTwoFewManyOther<T> {
+    fn select(self, category: PluralCategory) -> T {
+        match category {
+            PluralCategory::Zero => self.zero,
+            PluralCategory::One => self.one,
+            PluralCategory::Two => self.two,
+            PluralCategory::Few => self.few,
+            PluralCategory::Many => self.many,
+            PluralCategory::Other => self.other,
+        }
+    }
+}
+
+/// Gender forms of languages without grammatical gender.
+#[derive(Debug, Clone, Copy)]
+pub struct Ungendered<T> {
+    /// the only form
+    pub other: T,
+}
+impl<T> GenderForms<T> for Ungendered<T> {
+    fn select(self, _gender: Gender) -> T {
+        self.other
+    }
+}
+
+/// Gender forms of languages with masculine and feminine, neuter is masculine.
+#[derive(Debug, Clone, Copy)]
+pub struct MasculineFeminine<T> {
+    /// masculine
+    pub masculine: T,
+    /// feminine
+    pub feminine: T,
+}
+impl<T> GenderForms<T> for MasculineFeminine<T> {
+    fn select(self, gender: Gender) -> T {
+        match gender {
+            Gender::Feminine => self.feminine,
+            Gender::Masculine | Gender::Neuter => self.masculine,
+        }
+    }
+}
+
+/// Gender forms of languages with masculine, feminine and neuter.
+#[derive(Debug, Clone, Copy)]
+pub struct MasculineFeminineNeuter<T> {
+    /// masculine
+    pub masculine: T,
+    /// feminine
+    pub feminine: T,
+    /// neuter
+    pub neuter: T,
+}
+impl<T> GenderForms<T> for MasculineFeminineNeuter<T> {
+    fn select(self, gender: Gender) -> T {
+        match gender {
+            Gender::Masculine => self.masculine,
+            Gender::Feminine => self.feminine,
+            Gender::Neuter => self.neuter,
+        }
+    }
+}
+
+/// one: 1
+impl Plural for English {
+    type Forms<T> = OneOther<T>;
+
+    fn category(n: u64) -> PluralCategory {
+        match n {
+            1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+impl Gendered for English {
+    type Forms<T> = Ungendered<T>;
+}
+
+/// one: 0, 1; many: multiples of a million
+impl Plural for French {
+    type Forms<T> = OneManyOther<T>;
+
+    fn category(n: u64) -> PluralCategory {
+        match n {
+            0 | 1 => PluralCategory::One,
+            n if n % 1_000_000 == 0 => PluralCategory::Many,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+impl Gendered for French {
+    type Forms<T> = MasculineFeminine<T>;
+}
+
+/// one: 1
+impl Plural for German {
+    type Forms<T> = OneOther<T>;
+
+    fn category(n: u64) -> PluralCategory {
+        match n {
+            1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+impl Gendered for German {
+    type Forms<T> = MasculineFeminineNeuter<T>;
+}
+
+/// zero: 0; one: 1; two: 2; few: 3 to 10 modulo 100; many: 11 to 99 modulo 100
+impl Plural for Arabic {
+    type Forms<T> = ZeroOneTwoFewManyOther<T>;
+
+    fn category(n: u64) -> PluralCategory {
+        match (n, n % 100) {
+            (0, _) => PluralCategory::Zero,
+            (1, _) => PluralCategory::One,
+            (2, _) => PluralCategory::Two,
+            (_, 3..=10) => PluralCategory::Few,
+            (_, 11..=99) => PluralCategory::Many,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+impl Gendered for Arabic {
+    type Forms<T> = MasculineFeminine<T>;
+}
+
+/// one: 1; two: 2
+impl Plural for Hebrew {
+    type Forms<T> = OneTwoOther<T>;
+
+    fn category(n: u64) -> PluralCategory {
+        match n {
+            1 => PluralCategory::One,
+            2 => PluralCategory::Two,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+impl Gendered for Hebrew {
+    type Forms<T> = MasculineFeminine<T>;
+}
+
+/// Regional variants follow the rules of their base language.
+impl<R: Regional> Plural for R
+where
+    R::Base: Plural,
+{
+    type Forms<T> = <R::Base as Plural>::Forms<T>;
+
+    fn category(n: u64) -> PluralCategory {
+        R::Base::category(n)
+    }
+}
+impl<R: Regional> Gendered for R
+where
+    R::Base: Gendered,
+{
+    type Forms<T> = <R::Base as Gendered>::Forms<T>;
+}
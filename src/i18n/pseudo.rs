@@ -0,0 +1,218 @@
+//! Pseudo-localization, for testing layouts without real translations.
+//!
+//! [Pseudo] is the `en-XA` pseudo-locale: every type with an [English] template in
+//! [translations!](crate::translations!) is also translated into [Pseudo], by transforming
+//! the template with [pseudolocalize].
+//!
+//! - letters are accented, hard-coded strings bypassing [Localize] stay plain
+//! - the text is expanded by 40%, as many languages are longer than English
+//! - the text is bracketed, truncated text misses its closing bracket
+//!
+//! Only the template is transformed, the interpolated arguments are kept verbatim.
+//! ```
+//! # use bear_witness::i18n::*;
+//! #
+//! let context = Context { who: "World".to_string() };
+//! assert_eq!(render(context.localize(Pseudo)), "[Ĥéļļö World ~~~]");
+//!
+//! bear_witness::message! {
+//!     pub Unread = "unread" {
+//!         pub count: u64,
+//!     } {
+//!         English => "You have {count} unread messages",
+//!     }
+//! }
+//! let unread = Unread { count: 3 };
+//! assert_eq!(render(unread.localize(Pseudo)), "[Ýöû ĥáṽé 3 ûñŕéáð ɱéššáĝéš ~~~~~~~~~~]");
+//!
+//! let inbox = Inbox { who: "Camille".to_string(), unread: 1 };
+//! assert_eq!(render(inbox.localize(Pseudo)), "[Camille, ýöû ĥáṽé 1 ûñŕéáð ɱéššáĝé ~~~~~~~~~~~]");
+//! ```
+//!
+//! ```
+//! # use bear_witness::i18n::pseudo::*;
+//! #
+//! assert_eq!(pseudolocalize(""), "[]");
+//! assert_eq!(pseudolocalize("A-Z, 42!"), "[Å-Ž, 42! ~~~~]");
+//! ```
+//!
+//! The pseudo-locale is reported by the [Translations](super::Translations) and the
+//! [coverage](super::coverage) of the type, and served by [dispatch](super::dispatch),
+//! but it is never offered to users: it is not in [Language::ALL](super::Language::ALL),
+//! and [negotiate](super::negotiate) skips it.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::coverage::*;
+//! # use bear_witness::i18n::negotiate::*;
+//! #
+//! let context = Context { who: "World".to_string() };
+//! assert_eq!(dispatch(&context, Language::Pseudo), Ok("[Ĥéļļö World ~~~]".to_string()));
+//! assert_eq!(Context::languages(), vec![Language::English, Language::French, Language::Pseudo]);
+//!
+//! let coverage = Registry::default().register::<Context>().coverage();
+//! assert!(coverage.covers::<Context>(Language::Pseudo));
+//!
+//! assert!(!Language::ALL.contains(&Language::Pseudo));
+//! assert_eq!(negotiate::<Context>("en-XA"), Some(Language::English));
+//! assert_eq!(filter(&parse("*"), &Context::languages()), vec![Language::English, Language::French]);
+//! ```
+//!
+//! The pseudo-locale is not a regional variant of English, it never falls back to plain English.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::region::*;
+//! #
+//! let context = Context { who: "World".to_string() };
+//! render(context.localize_region(Pseudo));
+//! // error: the trait bound `Pseudo: Regional` is not satisfied
+//! ```
+//!
+//! Hand-written [Localize]\<[English]\> impls only produce the rendered string, the arguments
+//! cannot be told apart from the template anymore, and pseudolocalizing them would garble the
+//! names and numbers a tester checks. A blanket impl over them would also overlap with the one
+//! over [Template]\<[English]\>. Such types opt in by implementing [Template] with [argument].
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::bidi::*;
+//! # use bear_witness::i18n::pseudo::*;
+//! #
+//! struct Greeting {
+//!     who: String,
+//! }
+//! impl Localize<English> for Greeting {
+//!     fn translate(&self, lang: &English) -> String {
+//!         format!("Hello {}!", lang.isolate(&self.who))
+//!     }
+//! }
+//! impl Template<English> for Greeting {
+//!     fn format_arguments(&self, lang: &English) -> String {
+//!         format!("Hello {}!", argument(lang, &self.who))
+//!     }
+//! }
+//!
+//! let greeting = Greeting { who: "World".to_string() };
+//! assert_eq!(render(greeting.localize(Pseudo)), "[Ĥéļļö World! ~~~]");
+//! ```
+//!
+//! Types without an English template do not get a pseudo one.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! struct Farewell;
+//! impl Localize<German> for Farewell {
+//!     fn translate(&self, _lang: &German) -> String {
+//!         "Auf Wiedersehen".to_string()
+//!     }
+//! }
+//! render(Localize::<Pseudo>::localize(Farewell, Pseudo));
+//! // error: the trait `Template<English>` is not implemented for `Farewell`
+//! ```
+
+use std::fmt::{self, Write};
+
+use super::bidi::{Bidi, Isolated};
+use super::fallback::{Bool, Contains};
+use super::format::{Format, Profile};
+use super::plural::{Gendered, Plural, PluralCategory};
+use super::{English, Localize, Pseudo, TypedLang};
+
+const PLAIN: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const ACCENTED: &str = "áƀçðéƒĝĥîĵķļɱñöþǫŕšţûṽŵẋýžÅƁÇÐÉƑĜĤÎĴĶĻṀÑÖÞǪŔŠŢÛṼŴẊÝŽ";
+
+/// Noncharacters delimiting an [Argument] in a formatted template.
+const ARGUMENT_START: char = '\u{fdd0}';
+const ARGUMENT_END: char = '\u{fdd1}';
+
+/// Accent the letters, expand the text by 40% with `~` and bracket it.
+///
+/// The [Argument]s formatted into the text are kept verbatim, and do not count for the expansion.
+pub fn pseudolocalize(text: &str) -> String {
+    let mut pseudo = String::from("[");
+    let mut depth = 0_usize;
+    let mut length = 0_usize;
+    for c in text.chars() {
+        match c {
+            ARGUMENT_START => depth += 1,
+            ARGUMENT_END => depth = depth.saturating_sub(1),
+            c if depth > 0 => pseudo.push(c),
+            c => {
+                let accented = PLAIN.find(c).and_then(|i| ACCENTED.chars().nth(i));
+                pseudo.push(accented.unwrap_or(c));
+                length += 1;
+            }
+        }
+    }
+    let expansion = (length * 2).div_ceil(5);
+    if expansion > 0 {
+        pseudo.push(' ');
+        pseudo.push_str(&"~".repeat(expansion));
+    }
+    pseudo.push(']');
+    pseudo
+}
+
+/// An argument formatted into a template, marked so [pseudolocalize] keeps it verbatim.
+pub struct Argument<T>(pub T);
+impl<T: fmt::Display> fmt::Display for Argument<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char(ARGUMENT_START)?;
+        self.0.fmt(f)?;
+        f.write_char(ARGUMENT_END)
+    }
+}
+impl<T: fmt::Debug> fmt::Debug for Argument<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char(ARGUMENT_START)?;
+        self.0.fmt(f)?;
+        f.write_char(ARGUMENT_END)
+    }
+}
+
+/// Wrap an argument of a template, [isolated](Bidi::isolated) and marked as an [Argument].
+pub fn argument<L: TypedLang, T>(lang: &L, value: T) -> Argument<Isolated<L, T>> {
+    Argument(lang.isolated(value))
+}
+
+/// The template of the language `L`, generated by [translations!](crate::translations!).
+pub trait Template<L: TypedLang> {
+    /// Format the template, with every argument wrapped in an [Argument].
+    fn format_arguments(&self, lang: &L) -> String;
+}
+
+impl<T: Template<English> + ?Sized> Localize<Pseudo> for T {
+    fn translate(&self, _lang: &Pseudo) -> String {
+        pseudolocalize(&self.format_arguments(&English))
+    }
+}
+
+/// The type-level list of [TypedLang]s `Ls`, with [Pseudo] appended if it contains [English].
+pub type WithPseudo<Ls> =
+    <<Ls as Contains<English>>::Output as Bool>::If<<Ls as Append<Pseudo>>::Output, Ls>;
+
+/// Append to a type-level list of [TypedLang]s.
+pub trait Append<L> {
+    /// The list, followed by `L`.
+    type Output;
+}
+impl<L> Append<L> for () {
+    type Output = (L, ());
+}
+impl<L, H, Rest: Append<L>> Append<L> for (H, Rest) {
+    type Output = (H, Rest::Output);
+}
+
+impl Format for Pseudo {
+    const PROFILE: Profile = English::PROFILE;
+}
+
+/// English rules.
+impl Plural for Pseudo {
+    type Forms<T> = <English as Plural>::Forms<T>;
+
+    fn category(n: u64) -> PluralCategory {
+        English::category(n)
+    }
+}
+impl Gendered for Pseudo {
+    type Forms<T> = <English as Gendered>::Forms<T>;
+}
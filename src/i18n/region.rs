@@ -0,0 +1,266 @@
+//! Regional variants of languages, and BCP-47 language tags.
+//!
+//! A regional variant, e.g. [EnglishGB](super::EnglishGB), is a [TypedLang] of its own, [Regional] relates it
+//! to its base language. A type translated into the base language serves the region too,
+//! the [Fallback] tells whether a region-specific translation was used.
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::region::*;
+//! #
+//! struct Favourite {
+//!     colour: String,
+//! }
+//! bear_witness::translations! {
+//!     Favourite { colour } {
+//!         English => "My favorite color is {colour}",
+//!         EnglishGB => "My favourite colour is {colour}",
+//!     }
+//! }
+//!
+//! let favourite = || Favourite { colour: "green".to_string() };
+//! let localized = favourite().localize_region(EnglishGB);
+//! assert!(!localized.fell_back());
+//! assert_eq!(render(localized), "My favourite colour is green");
+//! let localized = favourite().localize_region(EnglishUS);
+//! assert!(localized.fell_back());
+//! assert_eq!(render(localized), "My favorite color is green");
+//!
+//! // Context only has the base languages
+//! let context = Context { who: "World".to_string() };
+//! assert_eq!(render(context.localize_region(FrenchCA)), "Bonjour World");
+//! ```
+//!
+//! At runtime, [LanguageTag] parses any well-formed tag and maps it onto the closest [Language].
+//! ```
+//! # use bear_witness::i18n::*;
+//! # use bear_witness::i18n::region::*;
+//! #
+//! let tag: LanguageTag = "de-latn-ch-1996-u-co-phonebk".parse().unwrap();
+//! assert_eq!(tag.language, "de");
+//! assert_eq!(tag.script.as_deref(), Some("Latn"));
+//! assert_eq!(tag.region.as_deref(), Some("CH"));
+//! assert_eq!(tag.variants, vec!["1996"]);
+//! assert_eq!(tag.extensions, vec!["u-co-phonebk"]);
+//! assert_eq!(tag.to_string(), "de-Latn-CH-1996-u-co-phonebk");
+//! assert_eq!(tag.to_language(), Some(Language::GermanCH));
+//!
+//! let to_language = |tag: &str| tag.parse::<LanguageTag>().ok()?.to_language();
+//! assert_eq!(to_language("en-GB"), Some(Language::EnglishGB));
+//! assert_eq!(to_language("EN-gb"), Some(Language::EnglishGB));
+//! assert_eq!(to_language("en-AU"), Some(Language::English));
+//! assert_eq!(to_language("fr-CA-x-quebec"), Some(Language::FrenchCA));
+//! assert_eq!(to_language("es-419"), None);
+//! // the pseudo-locales are never offered, see the pseudo module
+//! assert_eq!(to_language("en-XA"), Some(Language::English));
+//!
+//! assert_eq!("".parse::<LanguageTag>(), Err(TagError::Empty));
+//! assert_eq!("e".parse::<LanguageTag>(), Err(TagError::InvalidLanguage("e".to_string())));
+//! assert_eq!("en--GB".parse::<LanguageTag>(), Err(TagError::InvalidSubtag("".to_string())));
+//! assert_eq!("en_GB".parse::<LanguageTag>(), Err(TagError::InvalidLanguage("en_GB".to_string())));
+//! assert_eq!("en-u".parse::<LanguageTag>(), Err(TagError::InvalidSubtag("u".to_string())));
+//! ```
+//!
+//! Runtime [dispatch](super::dispatch) falls back to the base language the same way,
+//! a region is served by every type translated into its base language.
+//! ```
+//! # use bear_witness::i18n::*;
+//! #
+//! let context = Context { who: "World".to_string() };
+//! assert_eq!(dispatch(&context, Language::EnglishGB), Ok("Hello World".to_string()));
+//! assert_eq!(dispatch(&context, Language::FrenchCA), Ok("Bonjour World".to_string()));
+//! assert_eq!(dispatch(&context, Language::GermanCH), Err(Unsupported(Language::GermanCH)));
+//! ```
+//!
+//! Statically, a region is only served through [LocalizeRegion::localize_region],
+//! [Localize] alone is implemented for the listed languages only.
+//! ```compile_fail
+//! # use bear_witness::i18n::*;
+//! #
+//! let context = Context { who: "World".to_string() };
+//! render(context.localize(EnglishGB));
+//! // error: the trait `Localize<EnglishGB>` is not implemented for `Context`
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::fallback::{Fallback, LocalizeFallback, Resolve};
+use super::{Language, Localize, Translations, TypedLang};
+
+/// A regional variant of the language [Regional::Base].
+pub trait Regional: TypedLang + Default {
+    /// The base language.
+    type Base: TypedLang + Default;
+}
+
+/// Fallback chain of a region: the region, then its base language.
+pub type RegionChain<R> = (R, (<R as Regional>::Base, ()));
+
+/// Localize into a regional variant, or its base language.
+pub trait LocalizeRegion: Translations + Sized {
+    /// Localize into the `region` if translated, its [Regional::Base] otherwise.
+    fn localize_region<R: Regional>(
+        self,
+        region: R,
+    ) -> Fallback<Self, R, <RegionChain<R> as Resolve<Self>>::Resolved>
+    where
+        RegionChain<R>: Resolve<Self>,
+        <RegionChain<R> as Resolve<Self>>::Resolved: TypedLang + Default,
+        Self: Localize<<RegionChain<R> as Resolve<Self>>::Resolved>,
+    {
+        self.localize_fallback((region, (R::Base::default(), ())))
+    }
+}
+impl<T: Translations> LocalizeRegion for T {}
+
+/// A well-formed BCP-47 language tag, with canonical casing.
+///
+/// Extended language subtags and grandfathered tags are not supported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageTag {
+    /// Primary language subtag, lowercase: `de`.
+    pub language: String,
+    /// Script subtag, titlecase: `Latn`.
+    pub script: Option<String>,
+    /// Region subtag, uppercase: `CH`, `419`.
+    pub region: Option<String>,
+    /// Variant subtags, lowercase: `1996`.
+    pub variants: Vec<String>,
+    /// Extensions and private use, lowercase with their singleton: `u-co-phonebk`, `x-quebec`.
+    pub extensions: Vec<String>,
+}
+
+/// The language tag is not well-formed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagError {
+    /// The tag is empty.
+    Empty,
+    /// The primary language subtag is not 2 to 8 letters.
+    InvalidLanguage(String),
+    /// A subtag is out of place or malformed.
+    InvalidSubtag(String),
+}
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagError::Empty => write!(f, "empty language tag"),
+            TagError::InvalidLanguage(subtag) => write!(f, "invalid language subtag {:?}", subtag),
+            TagError::InvalidSubtag(subtag) => write!(f, "invalid subtag {:?}", subtag),
+        }
+    }
+}
+impl std::error::Error for TagError {}
+
+fn is_alpha(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_digit(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_alphanumeric(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+impl LanguageTag {
+    /// Parse a tag, case-insensitively.
+    pub fn parse(tag: &str) -> Result<Self, TagError> {
+        if tag.is_empty() {
+            return Err(TagError::Empty);
+        }
+        let mut subtags = tag.split('-');
+        let language = subtags.next().unwrap_or_default();
+        if !(2..=8).contains(&language.len()) || !is_alpha(language) {
+            return Err(TagError::InvalidLanguage(language.to_string()));
+        }
+        let subtags: Vec<&str> = subtags.collect();
+        let mut parsed = LanguageTag {
+            language: language.to_ascii_lowercase(),
+            ..LanguageTag::default()
+        };
+
+        let mut i = 0;
+        if let Some(script) = subtags.get(i).filter(|s| s.len() == 4 && is_alpha(s)) {
+            let (first, rest) = script.split_at(1);
+            parsed.script = Some(first.to_ascii_uppercase() + &rest.to_ascii_lowercase());
+            i += 1;
+        }
+        if let Some(region) = subtags
+            .get(i)
+            .filter(|s| (s.len() == 2 && is_alpha(s)) || (s.len() == 3 && is_digit(s)))
+        {
+            parsed.region = Some(region.to_ascii_uppercase());
+            i += 1;
+        }
+        while let Some(variant) = subtags.get(i).filter(|s| {
+            is_alphanumeric(s)
+                && ((5..=8).contains(&s.len())
+                    || (s.len() == 4 && s.as_bytes()[0].is_ascii_digit()))
+        }) {
+            parsed.variants.push(variant.to_ascii_lowercase());
+            i += 1;
+        }
+        while i < subtags.len() {
+            let singleton = subtags[i];
+            if singleton.len() != 1 || !is_alphanumeric(singleton) {
+                return Err(TagError::InvalidSubtag(singleton.to_string()));
+            }
+            // private use subtags may be a single character, extension subtags may not
+            let min = if singleton.eq_ignore_ascii_case("x") {
+                1
+            } else {
+                2
+            };
+            let start = i;
+            i += 1;
+            while let Some(subtag) = subtags.get(i) {
+                if !(min..=8).contains(&subtag.len()) || !is_alphanumeric(subtag) {
+                    break;
+                }
+                i += 1;
+            }
+            if i == start + 1 {
+                return Err(TagError::InvalidSubtag(singleton.to_string()));
+            }
+            parsed
+                .extensions
+                .push(subtags[start..i].join("-").to_ascii_lowercase());
+        }
+        Ok(parsed)
+    }
+
+    /// The closest [Language]: the regional variant if there is one, the base language otherwise.
+    ///
+    /// The [pseudo-locales](Language::PSEUDO) are skipped.
+    pub fn to_language(&self) -> Option<Language> {
+        let regional = self
+            .region
+            .as_ref()
+            .and_then(|region| Language::from_code(&format!("{}-{}", self.language, region)))
+            .filter(|language| !language.is_pseudo());
+        regional.or_else(|| Language::from_code(&self.language))
+    }
+}
+impl FromStr for LanguageTag {
+    type Err = TagError;
+
+    fn from_str(tag: &str) -> Result<Self, TagError> {
+        LanguageTag::parse(tag)
+    }
+}
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.language)?;
+        for subtag in self
+            .script
+            .iter()
+            .chain(&self.region)
+            .chain(&self.variants)
+            .chain(&self.extensions)
+        {
+            write!(f, "-{}", subtag)?;
+        }
+        Ok(())
+    }
+}
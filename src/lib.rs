@@ -1,36 +1,166 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+use std::marker::PhantomData;
+
 pub mod auth;
 pub mod bears;
 pub mod equals;
 pub mod i18n;
+pub mod non_empty;
+pub mod refine;
+pub mod witness;
 
 /// A simple transparent wrapper.
 ///
 /// We use it as a certificate of a successful type-check.
 /// Return it from a `witness` function, proving we have type-checked the value.
-pub struct Certified<T>(T);
-impl<T> Certified<T> {
-    /// Create a new [Certified] value.
-    pub fn new(t: T) -> Self {
-        Self(t)
+///
+/// The zero-sized `P` parameter records which check was performed,
+/// e.g. [ProvenBear](bears::ProvenBear) for [bear_witness](bears::bear_witness).
+/// A value can hold several proofs at once as a pair: `Certified<T, (P, Q)>`.
+///
+/// ## Sealed construction
+///
+/// Creating a [Certified] value requires a value of the proof marker.
+/// Proof markers have a private field, so only the module owning the marker
+/// (and therefore its witness functions) can construct one.
+///
+/// ```
+/// # use bear_witness::Certified;
+/// #
+/// /// Only this module can construct [Checked].
+/// pub struct Checked(());
+///
+/// pub fn checked_witness<T>(value: T) -> Certified<T, Checked> {
+///     Certified::new(value, Checked(()))
+/// }
+/// ```
+///
+/// We cannot skip [bear_witness](bears::bear_witness) and certify a [Dog](bears::Dog) directly.
+/// ```compile_fail
+/// # use bear_witness::bears::*;
+/// # use bear_witness::Certified;
+/// #
+/// let forged = Certified::new(Dog, ProvenBear(()));
+/// // error: cannot initialize a tuple struct which contains private fields
+/// ```
+///
+/// ```compile_fail
+/// # use bear_witness::bears::*;
+/// # use bear_witness::Certified;
+/// # use std::marker::PhantomData;
+/// #
+/// let forged: Certified<Dog, ProvenBear> = Certified(Dog, PhantomData);
+/// // error: cannot initialize a tuple struct which contains private fields
+/// ```
+pub struct Certified<T, P>(T, PhantomData<P>);
+impl<T, P> Certified<T, P> {
+    /// Create a new [Certified] value, consuming the `proof`.
+    pub fn new(t: T, _proof: P) -> Self {
+        Self(t, PhantomData)
+    }
+
+    /// Drop all the proofs, returning the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    /// Run another check over the value, keeping the proof we already hold.
+    ///
+    /// The check only borrows the value and returns it [Certified] by the new proof, e.g.
+    /// [refine::prove] or the `of` function generated by [witness!]. The proof has to certify
+    /// this very value, so it cannot be swapped for another one.
+    /// The value is returned back if the check fails.
+    ///
+    /// ```
+    /// # use bear_witness::bears::*;
+    /// # use bear_witness::refine::*;
+    /// # use bear_witness::Certified;
+    /// #
+    /// struct ProvenBrown(());
+    /// fn brown_proof(bear: &BrownBear) -> Option<Certified<&BrownBear, ProvenBrown>> {
+    ///     Some(Certified::new(bear, ProvenBrown(())))
+    /// }
+    ///
+    /// let bear = bear_witness(BrownBear).also(brown_proof).ok().unwrap();
+    /// // -> Certified<BrownBear, (ProvenBear, ProvenBrown)>
+    /// println!("{}", bear.growl());
+    ///
+    /// let name = refine::<NonEmpty, _>("Bear".to_string()).unwrap();
+    /// let name = name.also(prove::<MaxLen<8>, _>).ok().unwrap();
+    /// // -> Certified<String, (NonEmpty, MaxLen<8>)>
+    /// assert!(name.also(prove::<MaxLen<2>, _>).is_err());
+    /// ```
+    ///
+    /// A proof certified for some other value is rejected, the value never leaves `self`.
+    /// ```
+    /// # use bear_witness::refine::*;
+    /// #
+    /// static EMPTY: String = String::new();
+    ///
+    /// let name = refine::<NonEmpty, _>("x".to_string()).unwrap();
+    /// let name = name.also(|_| prove::<MaxLen<0>, _>(&EMPTY)).unwrap_err();
+    /// assert_eq!(*name, "x");
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use bear_witness::refine::*;
+    /// # use bear_witness::Certified;
+    /// #
+    /// let name = refine::<NonEmpty, _>("x".to_string()).unwrap();
+    /// let forged: Certified<String, (NonEmpty, MaxLen<0>)> = name
+    ///     .also(|_| refine::<MaxLen<0>, _>(String::new()).ok())
+    ///     .unwrap();
+    /// // error: mismatched types, expected `Option<Certified<&String, _>>`, found `Option<Certified<String, MaxLen<0>>>`
+    /// ```
+    pub fn also<Q>(
+        self,
+        check: impl FnOnce(&T) -> Option<Certified<&T, Q>>,
+    ) -> Result<Certified<T, (P, Q)>, Self> {
+        let proven = check(&self.0).is_some_and(|proof| std::ptr::eq(proof.0, &self.0));
+        if proven {
+            Ok(Certified(self.0, PhantomData))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T, P, Q> Certified<T, (P, Q)> {
+    /// Explicitly drop the first proof.
+    pub fn drop_first(self) -> Certified<T, Q> {
+        Certified(self.0, PhantomData)
+    }
+
+    /// Explicitly drop the second proof.
+    pub fn drop_second(self) -> Certified<T, P> {
+        Certified(self.0, PhantomData)
     }
 }
 
-impl<T> std::ops::Deref for Certified<T> {
+impl<T, P> std::ops::Deref for Certified<T, P> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl<T> Clone for Certified<T>
+impl<T, P> Clone for Certified<T, P>
 where
     T: Clone,
 {
     fn clone(&self) -> Self {
-        Certified(self.0.clone())
+        Certified(self.0.clone(), PhantomData)
+    }
+}
+impl<T, P> Copy for Certified<T, P> where T: Copy {}
+
+impl<T, P> std::fmt::Debug for Certified<T, P>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Certified").field(&self.0).finish()
     }
 }
-impl<T> Copy for Certified<T> where T: Copy {}
@@ -0,0 +1,136 @@
+//! # non-empty collections
+//!
+//! The `first_character` example from the crate docs panics on an empty [String].
+//! Refining the value with [NonEmpty] once lets us replace every `.unwrap()` with a
+//! total accessor returning a value instead of an [Option].
+//!
+//! ```
+//! # use bear_witness::non_empty::*;
+//! #
+//! fn first_character(s: &NonEmptyString) -> char {
+//!     s.first() // -> char
+//! }
+//!
+//! let s = non_empty("Hello".to_string()).unwrap();
+//! assert_eq!(first_character(&s), 'H');
+//! assert_eq!(s.last(), 'o');
+//! // the iterators of the value are left untouched
+//! assert_eq!(s.chars().next(), Some('H'));
+//!
+//! // the empty string is rejected once, when constructing the witness
+//! assert!(non_empty("".to_string()).is_err());
+//! ```
+//!
+//! The same works for [Vec] and slices.
+//! ```
+//! # use bear_witness::non_empty::*;
+//! #
+//! let mut bears = non_empty(vec!["brown", "polar"]).unwrap(); // -> NonEmptyVec<&str>
+//! bears.push("panda");
+//! assert_eq!(*bears.first(), "brown");
+//! assert_eq!(*bears.last(), "panda");
+//!
+//! let numbers = [1, 2, 3];
+//! let slice: NonEmptySlice<i32> = non_empty(&numbers[1..]).unwrap();
+//! assert_eq!(slice.split_first(), (&2, &[3][..]));
+//! ```
+//!
+//! A value which has not been through [non_empty] is not accepted.
+//! ```compile_fail
+//! # use bear_witness::non_empty::*;
+//! #
+//! # fn first_character(s: &NonEmptyString) -> char {
+//! #     s.first()
+//! # }
+//! first_character(&"".to_string());
+//! // error: mismatched types
+//! ```
+
+use crate::refine::{refine, Length, NonEmpty, RefinementError};
+use crate::Certified;
+
+/// A [String] with at least one character.
+pub type NonEmptyString = Certified<String, NonEmpty>;
+/// A [Vec] with at least one element.
+pub type NonEmptyVec<T> = Certified<Vec<T>, NonEmpty>;
+/// A slice with at least one element.
+pub type NonEmptySlice<'a, T> = Certified<&'a [T], NonEmpty>;
+
+/// Witness function checking the value is not empty.
+pub fn non_empty<T: Length>(value: T) -> Result<Certified<T, NonEmpty>, RefinementError<T>> {
+    refine(value)
+}
+
+impl NonEmptyString {
+    /// The first character.
+    pub fn first(&self) -> char {
+        match self.0.chars().next() {
+            Some(c) => c,
+            None => unreachable!("NonEmptyString is not empty"),
+        }
+    }
+
+    /// The last character.
+    pub fn last(&self) -> char {
+        match self.0.chars().next_back() {
+            Some(c) => c,
+            None => unreachable!("NonEmptyString is not empty"),
+        }
+    }
+
+    /// The first character and the rest of the string.
+    pub fn split_first(&self) -> (char, &str) {
+        let first = self.first();
+        (first, &self.0[first.len_utf8()..])
+    }
+}
+
+impl<T> NonEmptyVec<T> {
+    /// The first element.
+    pub fn first(&self) -> &T {
+        self.as_slice().first()
+    }
+
+    /// The last element.
+    pub fn last(&self) -> &T {
+        self.as_slice().last()
+    }
+
+    /// The first element and the rest of the elements.
+    pub fn split_first(&self) -> (&T, &[T]) {
+        self.as_slice().split_first()
+    }
+
+    /// Borrow as a [NonEmptySlice].
+    pub fn as_slice(&self) -> NonEmptySlice<'_, T> {
+        Certified::new(self.0.as_slice(), NonEmpty::proven())
+    }
+
+    /// Append an element, the [Vec] stays non-empty.
+    pub fn push(&mut self, value: T) {
+        self.0.push(value)
+    }
+}
+
+impl<'a, T> NonEmptySlice<'a, T> {
+    /// The first element.
+    pub fn first(&self) -> &'a T {
+        self.split_first().0
+    }
+
+    /// The last element.
+    pub fn last(&self) -> &'a T {
+        match self.0.split_last() {
+            Some((last, _)) => last,
+            None => unreachable!("NonEmptySlice is not empty"),
+        }
+    }
+
+    /// The first element and the rest of the elements.
+    pub fn split_first(&self) -> (&'a T, &'a [T]) {
+        match self.0.split_first() {
+            Some(split) => split,
+            None => unreachable!("NonEmptySlice is not empty"),
+        }
+    }
+}
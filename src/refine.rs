@@ -0,0 +1,359 @@
+//! # refinement types
+//!
+//! A refinement type is a type restricted by a predicate over its values,
+//! e.g. the non-empty [String] `first_character` really needs.
+//!
+//! The predicate is checked at runtime only once, by [refine].
+//! The returned [Certified] value is tagged with the predicate, a type witness
+//! that the check has been done.
+//!
+//! ```
+//! # use bear_witness::Certified;
+//! # use bear_witness::refine::*;
+//! #
+//! fn first_character(s: &Certified<String, NonEmpty>) -> char {
+//!     // no `unwrap` needed, see the [non_empty](crate::non_empty) module
+//!     s.first()
+//! }
+//!
+//! let s = refine::<NonEmpty, _>("Hello".to_string()).unwrap();
+//! assert_eq!(first_character(&s), 'H');
+//!
+//! let error = refine::<NonEmpty, _>("".to_string()).unwrap_err();
+//! assert_eq!(error.failed, vec![Violation::NonEmpty]);
+//! // we get the value back
+//! assert_eq!(error.value, "");
+//! ```
+//!
+//! A plain [String] is not accepted anymore.
+//! ```compile_fail
+//! # use bear_witness::Certified;
+//! # use bear_witness::refine::*;
+//! #
+//! # fn first_character(s: &Certified<String, NonEmpty>) -> char {
+//! #     s.first()
+//! # }
+//! first_character(&"".to_string());
+//! // error: mismatched types
+//! ```
+//!
+//! ## Combining predicates
+//!
+//! A pair of predicates is a predicate itself, the [RefinementError] lists all that failed.
+//! ```
+//! # use bear_witness::refine::*;
+//! #
+//! let name = refine::<(NonEmpty, MaxLen<5>), _>("Bear").unwrap();
+//! assert_eq!(name.len(), 4);
+//!
+//! let error = refine::<(NonEmpty, MaxLen<5>), _>("Polar Bear").unwrap_err();
+//! assert_eq!(error.failed, vec![Violation::MaxLen(5)]);
+//!
+//! let error = refine::<(Positive, InRange<10, 20>), _>(-1).unwrap_err();
+//! assert_eq!(error.failed, vec![Violation::Positive, Violation::InRange { lo: 10, hi: 20 }]);
+//! assert_eq!(error.to_string(), "-1 does not satisfy: Positive, InRange<10, 20>");
+//!
+//! // or add a predicate to a value already refined
+//! let name = name.also(prove::<MaxLen<4>, _>).ok().unwrap();
+//! assert_eq!(name.len(), 4);
+//!
+//! let sorted = refine::<Sorted, _>(vec![1, 2, 2, 3]).unwrap();
+//! assert_eq!(sorted.first(), Some(&1));
+//! assert!(refine::<Sorted, _>(vec![3, 2, 1]).is_err());
+//! ```
+//!
+//! Predicates can only be proven by [refine] and [prove], the markers cannot be constructed directly.
+//! ```compile_fail
+//! # use bear_witness::Certified;
+//! # use bear_witness::refine::*;
+//! #
+//! let forged = Certified::new("".to_string(), NonEmpty(()));
+//! // error: cannot initialize a tuple struct which contains private fields
+//! ```
+//!
+//! Checking a predicate directly only reports the violations, the proof marker is built
+//! by [refine] and [prove] for the value they checked. The predicates are sealed, so a
+//! predicate defined elsewhere cannot be handed the permission to build a marker and stash it.
+//! ```compile_fail
+//! # use bear_witness::Certified;
+//! # use bear_witness::refine::*;
+//! # use std::sync::Mutex;
+//! #
+//! static STASH: Mutex<Vec<NonEmpty>> = Mutex::new(Vec::new());
+//!
+//! struct Stash(());
+//! impl Predicate<String> for Stash {
+//!     fn check(_value: &String) -> Result<(), Vec<Violation>> {
+//!         Ok(())
+//!     }
+//!     fn proof(token: Token) -> Self {
+//!         STASH.lock().unwrap().push(<NonEmpty as Predicate<String>>::proof(token));
+//!         Stash(())
+//!     }
+//! }
+//!
+//! let _ = refine::<Stash, _>(String::new());
+//! let stashed = STASH.lock().unwrap().pop().unwrap();
+//! Certified::new(String::new(), stashed).first();
+//! // error: method `proof` is not a member of trait `Predicate`
+//! // error: the trait bound `Stash: Proof` is not satisfied
+//! ```
+
+use std::fmt;
+
+use crate::Certified;
+
+/// Check the [Predicate] `P` over a value.
+///
+/// Returns the value [Certified] by `P`, or a [RefinementError]
+/// listing the violated predicates.
+pub fn refine<P: Predicate<T>, T>(value: T) -> Result<Certified<T, P>, RefinementError<T>> {
+    match P::check(&value) {
+        Ok(()) => Ok(Certified::new(value, P::proof(sealed::Token(())))),
+        Err(failed) => Err(RefinementError { value, failed }),
+    }
+}
+
+/// Check the [Predicate] `P` over a borrowed value.
+///
+/// Returns the borrow [Certified] by `P`, to add the proof to an already
+/// certified value with [Certified::also].
+pub fn prove<P: Predicate<T>, T>(value: &T) -> Option<Certified<&T, P>> {
+    match P::check(value) {
+        Ok(()) => Some(Certified::new(value, P::proof(sealed::Token(())))),
+        Err(_) => None,
+    }
+}
+
+mod sealed {
+    /// Only this module can construct a [Token], and therefore a proof marker.
+    pub struct Token(pub(super) ());
+
+    pub trait Proof: Sized {
+        fn proof(token: Token) -> Self;
+    }
+}
+
+/// A predicate over values of type `T`.
+///
+/// The implementing type is the proof marker. [Predicate::check] only reports the
+/// violations, the marker is built by [refine] or [prove] for the value they checked.
+/// The predicates are sealed, only this module can define them.
+pub trait Predicate<T>: sealed::Proof {
+    /// Check the predicate, returning the list of violations if it does not hold.
+    fn check(value: &T) -> Result<(), Vec<Violation>>;
+}
+
+impl<T, P, Q> Predicate<T> for (P, Q)
+where
+    P: Predicate<T>,
+    Q: Predicate<T>,
+{
+    fn check(value: &T) -> Result<(), Vec<Violation>> {
+        match (P::check(value), Q::check(value)) {
+            (Ok(()), Ok(())) => Ok(()),
+            (Err(failed), Ok(())) | (Ok(()), Err(failed)) => Err(failed),
+            (Err(mut failed), Err(more)) => {
+                failed.extend(more);
+                Err(failed)
+            }
+        }
+    }
+}
+impl<P: sealed::Proof, Q: sealed::Proof> sealed::Proof for (P, Q) {
+    fn proof(token: sealed::Token) -> Self {
+        (P::proof(sealed::Token(())), Q::proof(token))
+    }
+}
+
+/// A predicate which did not hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// [NonEmpty] failed.
+    NonEmpty,
+    /// [Positive] failed.
+    Positive,
+    /// [InRange] failed.
+    InRange {
+        /// Inclusive lower bound.
+        lo: i64,
+        /// Inclusive upper bound.
+        hi: i64,
+    },
+    /// [MaxLen] failed.
+    MaxLen(usize),
+    /// [Sorted] failed.
+    Sorted,
+}
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::NonEmpty => write!(f, "NonEmpty"),
+            Violation::Positive => write!(f, "Positive"),
+            Violation::InRange { lo, hi } => write!(f, "InRange<{}, {}>", lo, hi),
+            Violation::MaxLen(max) => write!(f, "MaxLen<{}>", max),
+            Violation::Sorted => write!(f, "Sorted"),
+        }
+    }
+}
+
+/// The value did not pass [refine].
+#[derive(Debug)]
+pub struct RefinementError<T> {
+    /// The refused value, handed back to the caller.
+    pub value: T,
+    /// All the predicates which did not hold.
+    pub failed: Vec<Violation>,
+}
+impl<T: fmt::Debug> fmt::Display for RefinementError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} does not satisfy: ", self.value)?;
+        for (i, violation) in self.failed.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+impl<T: fmt::Debug> std::error::Error for RefinementError<T> {}
+
+/// Values with a length, used by [NonEmpty] and [MaxLen].
+pub trait Length {
+    /// Number of elements, or characters for strings.
+    fn length(&self) -> usize;
+}
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+impl Length for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+impl<L: Length + ?Sized> Length for &L {
+    fn length(&self) -> usize {
+        (**self).length()
+    }
+}
+
+/// The value is not empty.
+pub struct NonEmpty(());
+impl NonEmpty {
+    /// For values known to be non-empty without checking, e.g. a slice of a [NonEmpty] [Vec].
+    pub(crate) fn proven() -> Self {
+        NonEmpty(())
+    }
+}
+impl sealed::Proof for NonEmpty {
+    fn proof(_token: sealed::Token) -> Self {
+        NonEmpty(())
+    }
+}
+impl<T: Length> Predicate<T> for NonEmpty {
+    fn check(value: &T) -> Result<(), Vec<Violation>> {
+        if value.length() > 0 {
+            Ok(())
+        } else {
+            Err(vec![Violation::NonEmpty])
+        }
+    }
+}
+
+/// The value has at most `N` elements (characters for strings).
+pub struct MaxLen<const N: usize>(());
+impl<const N: usize> sealed::Proof for MaxLen<N> {
+    fn proof(_token: sealed::Token) -> Self {
+        MaxLen(())
+    }
+}
+impl<T: Length, const N: usize> Predicate<T> for MaxLen<N> {
+    fn check(value: &T) -> Result<(), Vec<Violation>> {
+        if value.length() <= N {
+            Ok(())
+        } else {
+            Err(vec![Violation::MaxLen(N)])
+        }
+    }
+}
+
+/// The value is strictly greater than zero.
+pub struct Positive(());
+impl sealed::Proof for Positive {
+    fn proof(_token: sealed::Token) -> Self {
+        Positive(())
+    }
+}
+
+/// The value is within `LO..=HI`.
+pub struct InRange<const LO: i64, const HI: i64>(());
+impl<const LO: i64, const HI: i64> sealed::Proof for InRange<LO, HI> {
+    fn proof(_token: sealed::Token) -> Self {
+        InRange(())
+    }
+}
+
+macro_rules! impl_integer_predicates {
+    ($($int:ty),*) => {$(
+        impl<const LO: i64, const HI: i64> Predicate<$int> for InRange<LO, HI> {
+            fn check(value: &$int) -> Result<(), Vec<Violation>> {
+                if (LO as i128..=HI as i128).contains(&(*value as i128)) {
+                    Ok(())
+                } else {
+                    Err(vec![Violation::InRange { lo: LO, hi: HI }])
+                }
+            }
+        }
+    )*};
+}
+impl_integer_predicates!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_positive {
+    ($($num:ty: $zero:expr),*) => {$(
+        impl Predicate<$num> for Positive {
+            fn check(value: &$num) -> Result<(), Vec<Violation>> {
+                if *value > $zero {
+                    Ok(())
+                } else {
+                    Err(vec![Violation::Positive])
+                }
+            }
+        }
+    )*};
+}
+impl_positive!(i8: 0, i16: 0, i32: 0, i64: 0, i128: 0, isize: 0, f32: 0.0, f64: 0.0);
+
+/// The elements are in non-decreasing order.
+pub struct Sorted(());
+impl sealed::Proof for Sorted {
+    fn proof(_token: sealed::Token) -> Self {
+        Sorted(())
+    }
+}
+impl<T: Ord> Predicate<Vec<T>> for Sorted {
+    fn check(value: &Vec<T>) -> Result<(), Vec<Violation>> {
+        <Sorted as Predicate<&[T]>>::check(&value.as_slice())
+    }
+}
+impl<T: Ord> Predicate<&[T]> for Sorted {
+    fn check(value: &&[T]) -> Result<(), Vec<Violation>> {
+        if value.windows(2).all(|pair| pair[0] <= pair[1]) {
+            Ok(())
+        } else {
+            Err(vec![Violation::Sorted])
+        }
+    }
+}
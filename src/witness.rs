@@ -0,0 +1,160 @@
+//! # generating witness functions
+//!
+//! [bear_witness](crate::bears::bear_witness) is written by hand for a single trait.
+//! Certifying many traits this way means copy-pasting the same function over and over.
+//!
+//! The [witness!](crate::witness!) macro generates the witness function together with
+//! a dedicated proof marker for any trait, the witness returns a [Certified] tagged with it.
+//!
+//! ```
+//! # use bear_witness::bears::*;
+//! # use bear_witness::Certified;
+//! #
+//! bear_witness::witness! {
+//!     /// Type check [Bear] without erasing type information.
+//!     pub fn is_bear(Bear) -> ProvenBearish;
+//!
+//!     /// Type check [Clone] without erasing type information.
+//!     pub fn is_clone(Clone) -> ProvenClone;
+//! }
+//!
+//! let certified_bear = is_bear(BrownBear); // -> Certified<BrownBear, ProvenBearish>
+//! // we can call [Bear#growl]
+//! println!("{}", certified_bear.growl());
+//! // and still call [BrownBear#do_brown_bear_things]
+//! println!("{}", certified_bear.do_brown_bear_things());
+//!
+//! let certified_clone = is_clone(String::from("clone me")); // -> Certified<String, ProvenClone>
+//! assert_eq!(certified_clone.into_inner(), "clone me");
+//!
+//! // the proofs are distinct, so they cannot be mixed up
+//! fn bears_only<T: Bear>(bear: Certified<T, ProvenBearish>) -> bool {
+//!     true
+//! }
+//! assert!(bears_only(is_bear(PolarBear)));
+//! ```
+//!
+//! The generated witness function still refuses values not implementing the trait.
+//! ```compile_fail
+//! # use bear_witness::bears::*;
+//! #
+//! bear_witness::witness! {
+//!     /// Type check [Bear].
+//!     pub fn is_bear(Bear) -> ProvenBearish;
+//! }
+//!
+//! let certified_bear = is_bear(Dog);
+//! // error: the trait `Bear` is not implemented for `Dog`
+//! ```
+//!
+//! Code outside the module invoking the macro cannot mint the proof either.
+//! ```compile_fail
+//! # use bear_witness::bears::*;
+//! # use bear_witness::Certified;
+//! #
+//! mod bears_only {
+//!     bear_witness::witness! {
+//!         /// Type check [Bear].
+//!         pub fn is_bear(bear_witness::bears::Bear) -> ProvenBearish;
+//!     }
+//! }
+//!
+//! let forged = Certified::new(Dog, bears_only::ProvenBearish(()));
+//! // error: cannot initialize a tuple struct which contains private fields
+//! ```
+//!
+//! A certificate for one trait is not accepted where another one is expected.
+//! ```compile_fail
+//! # use bear_witness::bears::*;
+//! # use bear_witness::Certified;
+//! #
+//! bear_witness::witness! {
+//!     /// Type check [Bear].
+//!     pub fn is_bear(Bear) -> ProvenBearish;
+//!     /// Type check [Clone].
+//!     pub fn is_clone(Clone) -> ProvenClone;
+//! }
+//!
+//! fn bears_only<T: Bear>(bear: Certified<T, ProvenBearish>) -> bool {
+//!     true
+//! }
+//! #[derive(Clone)]
+//! struct CloneBear;
+//! impl Bear for CloneBear {
+//!     fn growl(&self) -> &str {
+//!         "<growl> <growl>"
+//!     }
+//! }
+//! bears_only(is_clone(CloneBear));
+//! // error: mismatched types, expected `Certified<_, ProvenBearish>`, found `Certified<CloneBear, ProvenClone>`
+//! ```
+//!
+//! The bound can be any list of bounds, including several traits or higher-ranked ones.
+//! ```
+//! # use bear_witness::bears::*;
+//! #
+//! bear_witness::witness! {
+//!     /// Type check [Bear] and [Clone] at once.
+//!     pub fn is_clone_bear(Bear + Clone) -> ProvenCloneBear;
+//!
+//!     /// Type check a comparison with any borrowed `str`.
+//!     pub fn is_str_like(for<'a> PartialEq<&'a str>) -> ProvenStrLike;
+//! }
+//!
+//! #[derive(Clone)]
+//! struct CloneBear;
+//! impl Bear for CloneBear {
+//!     fn growl(&self) -> &str {
+//!         "<growl> <growl>"
+//!     }
+//! }
+//! let bear = is_clone_bear(CloneBear); // -> Certified<CloneBear, ProvenCloneBear>
+//! assert_eq!(bear.clone().growl(), "<growl> <growl>");
+//!
+//! // the generated `of` function proves a borrowed value, to add the proof to a certified one
+//! let bear = bear_witness(CloneBear).also(ProvenCloneBear::of).ok().unwrap();
+//! // -> Certified<CloneBear, (ProvenBear, ProvenCloneBear)>
+//! assert!(*is_str_like("x") == "x");
+//! ```
+
+#[cfg(doc)]
+use crate::Certified;
+
+/// Generate a witness function and a dedicated proof marker for a trait.
+///
+/// ```text
+/// witness! {
+///     /// Documentation of the witness function.
+///     pub fn <witness name>(<bounds>) -> <Proof name>;
+/// }
+/// ```
+///
+/// The bounds are anything allowed after `T:`, e.g. `Bear + Clone` or `for<'a> Fn(&'a str)`.
+/// The witness takes the value by value, so `?Sized` bounds are not supported.
+///
+/// The witness returns [Certified](crate::Certified) tagged with the proof.
+/// The `of` function of the proof certifies a borrowed value instead,
+/// for [Certified::also](crate::Certified::also).
+/// The proof can only be constructed in the module invoking the macro,
+/// see the [witness](mod@crate::witness) module for an example.
+#[macro_export]
+macro_rules! witness {
+    ($(
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($($bound:tt)+) -> $proof:ident;
+    )+) => {$(
+        $(#[$meta])*
+        $vis fn $name<T: $($bound)+>(value: T) -> $crate::Certified<T, $proof> {
+            $crate::Certified::new(value, $proof(()))
+        }
+
+        #[doc = concat!("Proof marker for [", stringify!($name), "], the value has been type-checked to impl `", stringify!($($bound)+), "`.")]
+        $vis struct $proof(());
+        impl $proof {
+            #[doc = concat!("Prove a borrowed value impls `", stringify!($($bound)+), "`, see [Certified::also](", stringify!($crate), "::Certified::also).")]
+            $vis fn of<T: $($bound)+>(value: &T) -> Option<$crate::Certified<&T, $proof>> {
+                Some($crate::Certified::new(value, $proof(())))
+            }
+        }
+    )+};
+}